@@ -0,0 +1,685 @@
+//! Code generation for build scripts.
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     fs_router::build::generate("pages").unwrap();
+//! }
+//!
+//! // src/main.rs
+//! mod pages {
+//!     include!(concat!(env!("OUT_DIR"), "/fs_router_pages.rs"));
+//! }
+//! ```
+//!
+//! Every file under the page directory is declared as a module and is
+//! expected to expose `pub fn page() -> crate::Page`. The generated
+//! `route_table()` returns a `RouteTable<crate::Page>` with one entry per page.
+
+use std::collections::HashSet;
+use std::env;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::errors::RouteError;
+use crate::grammar::parse_file_path;
+use crate::scan::scan_pages;
+use crate::spec::{ParamKind, RouteKind, RouteSpec};
+use crate::table::RouteTable;
+
+#[derive(Debug)]
+pub enum BuildError {
+    Io(io::Error),
+    /// A page whose file name is not valid route grammar.
+    Page {
+        source: String,
+        error: RouteError,
+    },
+    /// A page that cannot be inserted next to `existing`.
+    Conflict {
+        source: String,
+        existing: Option<String>,
+        error: RouteError,
+    },
+    /// A page with two params that become the same `Route` field, e.g.
+    /// `[post-id]` and `[post_id]`.
+    DuplicateField {
+        source: String,
+        field: String,
+    },
+    MissingOutDir,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::Io(err) => write!(f, "{err}"),
+            BuildError::Page { source, error } => write!(f, "{source} : {error}"),
+            BuildError::Conflict {
+                source,
+                existing: Some(existing),
+                error,
+            } => write!(f, "{source} conflicts with {existing} : {error}"),
+            BuildError::Conflict {
+                source,
+                existing: None,
+                error,
+            } => write!(f, "{source} : {error}"),
+            BuildError::DuplicateField { source, field } => {
+                write!(f, "{source} : more than one param becomes the field {field}")
+            }
+            BuildError::MissingOutDir => {
+                write!(f, "OUT_DIR is not set, call this from a build script")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+impl From<io::Error> for BuildError {
+    fn from(err: io::Error) -> Self {
+        BuildError::Io(err)
+    }
+}
+
+/// Generates `$OUT_DIR/fs_router_pages.rs` for the pages under `page_dir`
+/// using the default conventions.
+pub fn generate(page_dir: impl AsRef<Path>) -> Result<PathBuf, BuildError> {
+    Generator::new(page_dir).generate()
+}
+
+#[derive(Debug, Clone)]
+pub struct Generator {
+    page_dir: PathBuf,
+    handler: String,
+    constructor: String,
+    file_name: String,
+}
+
+/// A page file discovered under the page directory.
+#[derive(Debug, Clone)]
+pub struct Page {
+    pub module: String,
+    /// The file's path below the page directory, with `/` separators.
+    pub relative: String,
+    pub file: PathBuf,
+    pub spec: RouteSpec,
+}
+
+impl Generator {
+    pub fn new(page_dir: impl AsRef<Path>) -> Self {
+        Self {
+            page_dir: page_dir.as_ref().to_path_buf(),
+            handler: "crate::Page".to_string(),
+            constructor: "page".to_string(),
+            file_name: "fs_router_pages.rs".to_string(),
+        }
+    }
+
+    /// The type every page constructor returns, `crate::Page` by default.
+    pub fn handler(mut self, handler: impl Into<String>) -> Self {
+        self.handler = handler.into();
+        self
+    }
+
+    /// The function each page module exposes, `page` by default.
+    pub fn constructor(mut self, constructor: impl Into<String>) -> Self {
+        self.constructor = constructor.into();
+        self
+    }
+
+    pub fn file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = file_name.into();
+        self
+    }
+
+    /// Writes the generated module into `OUT_DIR` and asks cargo to rerun the
+    /// build script when the page directory changes.
+    pub fn generate(&self) -> Result<PathBuf, BuildError> {
+        let out_dir = env::var_os("OUT_DIR").ok_or(BuildError::MissingOutDir)?;
+        let out_file = Path::new(&out_dir).join(&self.file_name);
+
+        println!("cargo:rerun-if-changed={}", self.root().display());
+        fs::write(&out_file, self.render()?)?;
+
+        Ok(out_file)
+    }
+
+    /// The page directory on disk. Relative directories are resolved against
+    /// the crate being built.
+    pub fn root(&self) -> PathBuf {
+        if self.page_dir.is_absolute() {
+            return self.page_dir.clone();
+        }
+
+        match env::var_os("CARGO_MANIFEST_DIR") {
+            Some(manifest_dir) => Path::new(&manifest_dir).join(&self.page_dir),
+            None => self.page_dir.clone(),
+        }
+    }
+
+    /// Scans and parses every page, rejecting tables that would fail to build.
+    pub fn pages(&self) -> Result<Vec<Page>, BuildError> {
+        let root = self.root();
+        let page_dir = self.page_dir.to_string_lossy().replace('\\', "/");
+
+        let mut files = scan_pages(&root)?;
+        files.sort();
+
+        let mut modules = HashSet::new();
+        let mut pages = Vec::with_capacity(files.len());
+
+        for file in files {
+            let relative = file
+                .strip_prefix(&root)
+                .unwrap_or(&file)
+                .to_string_lossy()
+                .replace('\\', "/");
+
+            let source = format!("{page_dir}/{relative}");
+            let spec = parse_file_path(&source, Some(&page_dir))
+                .map_err(|error| BuildError::Page { source, error })?;
+
+            let mut fields = HashSet::new();
+            for param in &spec.params {
+                let field = field_name(&param.name);
+                if !fields.insert(field.clone()) {
+                    return Err(BuildError::DuplicateField {
+                        source: spec.source,
+                        field,
+                    });
+                }
+            }
+
+            let module = unique_module(&relative, &mut modules);
+
+            pages.push(Page {
+                module,
+                relative,
+                file,
+                spec,
+            });
+        }
+
+        let mut table = RouteTable::with_capacity(pages.len());
+        for (index, page) in pages.iter().enumerate() {
+            if let Err(error) = table.insert(page.spec.clone(), index) {
+                let existing = match &error {
+                    RouteError::ShadowedRoute(conflict) => Some(conflict.existing_source.clone()),
+                    _ => pages[..index]
+                        .iter()
+                        .find(|other| collides(&other.spec, &page.spec))
+                        .map(|other| other.spec.source.clone()),
+                };
+
+                return Err(BuildError::Conflict {
+                    source: page.spec.source.clone(),
+                    existing,
+                    error,
+                });
+            }
+        }
+
+        Ok(pages)
+    }
+
+    /// Renders the module declarations and the `route_table()` function.
+    pub fn render(&self) -> Result<String, BuildError> {
+        let pages = self.pages()?;
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "// @generated by fs_router::build from {:?}",
+            self.page_dir
+        );
+        out.push_str(&render_modules(&pages));
+
+        let _ = writeln!(
+            out,
+            "\n#[allow(dead_code)]\npub fn route_table() -> ::fs_router::RouteTable<{}> {{",
+            self.handler
+        );
+        let _ = writeln!(
+            out,
+            "    {}\n}}",
+            render_table_expr(&pages, &self.constructor)
+        );
+        out.push_str(&render_route_enum(&pages));
+
+        Ok(out)
+    }
+}
+
+pub fn render_modules(pages: &[Page]) -> String {
+    let mut out = String::new();
+
+    for page in pages {
+        let _ = writeln!(
+            out,
+            "\n#[path = {:?}]\npub mod {};",
+            page.file.to_string_lossy(),
+            page.module
+        );
+    }
+
+    out
+}
+
+pub fn render_table_expr(pages: &[Page], constructor: &str) -> String {
+    let mut out = String::from("::fs_router::RouteTable::from_routes([\n");
+
+    for page in pages {
+        let _ = writeln!(
+            out,
+            "        ({}, {}::{}()),",
+            render_spec(&page.spec),
+            page.module,
+            constructor
+        );
+    }
+
+    out.push_str("    ])\n    .expect(\"routes are validated when generated\")");
+    out
+}
+
+/// Renders a `Route` enum with one variant per page, so navigation targets
+/// and their params are checked by the compiler. Fallback pages are skipped
+/// since they cannot be navigated to.
+pub fn render_route_enum(pages: &[Page]) -> String {
+    let mut taken = HashSet::new();
+    let routes: Vec<(String, &RouteSpec)> = pages
+        .iter()
+        .filter(|page| !matches!(page.spec.kind, RouteKind::Fallback | RouteKind::Layout))
+        .map(|page| (unique_variant(&page.relative, &mut taken), &page.spec))
+        .collect();
+
+    let mut variants = String::new();
+    let mut specs = String::new();
+    let mut indices = String::new();
+    let mut to_path = String::new();
+    let mut from_path = String::new();
+
+    for (index, (variant, spec)) in routes.iter().enumerate() {
+        let fields = spec
+            .params
+            .iter()
+            .map(|param| field_name(&param.name))
+            .collect::<Vec<_>>();
+
+        let (pattern, any) = if fields.is_empty() {
+            (format!("Route::{variant}"), format!("Route::{variant}"))
+        } else {
+            (
+                format!("Route::{variant} {{ {} }}", fields.join(", ")),
+                format!("Route::{variant} {{ .. }}"),
+            )
+        };
+
+        let declared = spec
+            .params
+            .iter()
+            .map(|param| match param.kind {
+                ParamKind::Single => format!("{}: String", field_name(&param.name)),
+                ParamKind::Optional => format!("{}: Option<String>", field_name(&param.name)),
+                ParamKind::CatchAll | ParamKind::OptionalCatchAll => {
+                    format!("{}: Vec<String>", field_name(&param.name))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if declared.is_empty() {
+            let _ = writeln!(variants, "    {variant},");
+        } else {
+            let _ = writeln!(variants, "    {variant} {{ {} }},", declared.join(", "));
+        }
+
+        let _ = writeln!(specs, "            ({}, {index}usize),", render_spec(spec));
+        let _ = writeln!(indices, "            {any} => {index},");
+
+        let mut joins = String::new();
+        let mut args = Vec::new();
+        let mut captures = Vec::new();
+        for (param, field) in spec.params.iter().zip(&fields) {
+            match param.kind {
+                ParamKind::Single => {
+                    args.push(format!("({:?}, {field}.as_str())", param.name));
+                    captures.push(format!(
+                        "{field}: matched.param({:?})?.to_string()",
+                        param.name
+                    ));
+                }
+                ParamKind::CatchAll => {
+                    let _ = write!(joins, "let {field} = {field}.join(\"/\"); ");
+                    args.push(format!("({:?}, {field}.as_str())", param.name));
+                    captures.push(format!(
+                        "{field}: matched.param({:?})?.split('/').map(str::to_string).collect()",
+                        param.name
+                    ));
+                }
+                ParamKind::Optional => {
+                    args.push(format!(
+                        "({:?}, {field}.as_deref().unwrap_or_default())",
+                        param.name
+                    ));
+                    captures.push(format!(
+                        "{field}: matched.param({:?}).map(str::to_string)",
+                        param.name
+                    ));
+                }
+                ParamKind::OptionalCatchAll => {
+                    let _ = write!(joins, "let {field} = {field}.join(\"/\"); ");
+                    args.push(format!("({:?}, {field}.as_str())", param.name));
+                    captures.push(format!(
+                        "{field}: matched.param({:?}).map(|value| value.split('/').map(str::to_string).collect()).unwrap_or_default()",
+                        param.name
+                    ));
+                }
+            }
+        }
+
+        let _ = writeln!(
+            to_path,
+            "            {pattern} => {{ {joins}build(self.spec(), &[{}]) }}",
+            args.join(", ")
+        );
+
+        if captures.is_empty() {
+            let _ = writeln!(from_path, "            {index} => Route::{variant},");
+        } else {
+            let _ = writeln!(
+                from_path,
+                "            {index} => Route::{variant} {{ {} }},",
+                captures.join(", ")
+            );
+        }
+    }
+
+    if routes.is_empty() {
+        return EMPTY_ROUTE_ENUM.to_string();
+    }
+
+    format!(
+        r#"
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Route {{
+{variants}}}
+
+#[allow(dead_code)]
+fn route_specs() -> &'static ::fs_router::RouteTable<usize> {{
+    static TABLE: ::std::sync::OnceLock<::fs_router::RouteTable<usize>> = ::std::sync::OnceLock::new();
+    TABLE.get_or_init(|| {{
+        ::fs_router::RouteTable::from_routes([
+{specs}        ])
+        .expect("routes are validated when generated")
+    }})
+}}
+
+#[allow(dead_code)]
+impl Route {{
+    pub fn spec(&self) -> &'static ::fs_router::RouteSpec {{
+        let index = match self {{
+{indices}        }};
+        &route_specs().routes()[index].0
+    }}
+
+    pub fn id(&self) -> u64 {{
+        self.spec().id
+    }}
+
+    /// The route's path. Params are not checked against their matchers,
+    /// see `to_path_with`.
+    pub fn to_path(&self) -> String {{
+        self.build_with(::fs_router::build_path)
+            .expect("typed routes supply every param")
+    }}
+
+    /// The route's path, or `InvalidParam` when a param does not satisfy its
+    /// matcher in `matchers`, e.g. the app table's `matchers()`.
+    pub fn to_path_with(
+        &self,
+        matchers: &::fs_router::core::param_matcher::ParamMatchers,
+    ) -> Result<String, ::fs_router::NavigateError> {{
+        self.build_with(|spec, params| ::fs_router::build_path_with(spec, params, matchers))
+    }}
+
+    fn build_with(
+        &self,
+        build: impl Fn(&::fs_router::RouteSpec, &[(&str, &str)]) -> Result<String, ::fs_router::NavigateError>,
+    ) -> Result<String, ::fs_router::NavigateError> {{
+        match self {{
+{to_path}        }}
+    }}
+
+    /// Resolves `path` with the built-in param matchers only. With custom
+    /// matchers, resolve against the app's table and use `from_match`.
+    pub fn from_path(path: &str) -> Option<Self> {{
+        Self::from_match(&route_specs().resolve(path)?)
+    }}
+
+    /// The route of a match from any table built from these pages.
+    pub fn from_match(matched: &::fs_router::RouteMatch) -> Option<Self> {{
+        let (_, index) = route_specs()
+            .routes()
+            .iter()
+            .find(|(spec, _)| spec.id == matched.spec.id)?;
+        let route = match *index {{
+{from_path}            _ => return None,
+        }};
+        Some(route)
+    }}
+}}
+"#
+    )
+}
+
+/// The `Route` enum of a page tree with only fallbacks and layouts.
+const EMPTY_ROUTE_ENUM: &str = r#"
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Route {}
+
+#[allow(dead_code)]
+impl Route {
+    pub fn spec(&self) -> &'static ::fs_router::RouteSpec {
+        match *self {}
+    }
+
+    pub fn id(&self) -> u64 {
+        match *self {}
+    }
+
+    pub fn to_path(&self) -> String {
+        match *self {}
+    }
+
+    pub fn to_path_with(
+        &self,
+        _matchers: &::fs_router::core::param_matcher::ParamMatchers,
+    ) -> Result<String, ::fs_router::NavigateError> {
+        match *self {}
+    }
+
+    pub fn from_path(_path: &str) -> Option<Self> {
+        None
+    }
+
+    pub fn from_match(_matched: &::fs_router::RouteMatch) -> Option<Self> {
+        None
+    }
+}
+"#;
+
+pub fn render_spec(spec: &RouteSpec) -> String {
+    let params = spec
+        .params
+        .iter()
+        .map(|param| {
+            let matcher = match &param.matcher {
+                Some(matcher) => format!("Some({matcher:?}.to_string())"),
+                None => "None".to_string(),
+            };
+
+            format!(
+                "::fs_router::ParamSpec {{ name: {:?}.to_string(), kind: ::fs_router::core::ParamKind::{}, matcher: {} }}",
+                param.name,
+                param_kind_name(&param.kind),
+                matcher
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "::fs_router::RouteSpec {{ id: {}, path: {:?}.to_string(), kind: ::fs_router::RouteKind::{}, params: vec![{}], source: {:?}.to_string(), layouts: vec!{:?} }}",
+        spec.id,
+        spec.path,
+        route_kind_name(&spec.kind),
+        params,
+        spec.source,
+        spec.layouts
+    )
+}
+
+fn collides(a: &RouteSpec, b: &RouteSpec) -> bool {
+    match (&a.kind, &b.kind) {
+        (RouteKind::Fallback, RouteKind::Fallback) => a.path == b.path,
+        (RouteKind::Fallback, _) | (_, RouteKind::Fallback) => false,
+        (RouteKind::Layout, RouteKind::Layout) => a.id == b.id,
+        (RouteKind::Layout, _) | (_, RouteKind::Layout) => false,
+        _ => a.path == b.path || a.id == b.id,
+    }
+}
+
+fn route_kind_name(kind: &RouteKind) -> &'static str {
+    match kind {
+        RouteKind::Static => "Static",
+        RouteKind::Dynamic => "Dynamic",
+        RouteKind::CatchAll => "CatchAll",
+        RouteKind::Fallback => "Fallback",
+        RouteKind::Layout => "Layout",
+    }
+}
+
+fn param_kind_name(kind: &ParamKind) -> &'static str {
+    match kind {
+        ParamKind::Single => "Single",
+        ParamKind::CatchAll => "CatchAll",
+        ParamKind::OptionalCatchAll => "OptionalCatchAll",
+        ParamKind::Optional => "Optional",
+    }
+}
+
+fn unique_variant(relative: &str, taken: &mut HashSet<String>) -> String {
+    let stem = relative.strip_suffix(".rs").unwrap_or(relative);
+    let stem = without_matchers(stem);
+
+    let mut base = String::new();
+    for part in stem.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            base.push(first.to_ascii_uppercase());
+            base.push_str(chars.as_str());
+        }
+    }
+
+    if !base.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        base.insert_str(0, "Page");
+    }
+
+    let mut variant = base.clone();
+    let mut n = 2;
+    while !taken.insert(variant.clone()) {
+        variant = format!("{base}{n}");
+        n += 1;
+    }
+
+    variant
+}
+
+/// The snake_case field a param becomes, so `[post-id]` and `[postId]` are
+/// both `post_id`.
+fn field_name(param: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+        "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "type", "unsafe",
+        "use", "where", "while",
+    ];
+    const RESERVED: &[&str] = &["crate", "self", "super"];
+
+    let mut field = String::with_capacity(param.len());
+    let mut after_lower = false;
+    for c in param.chars() {
+        if c.is_ascii_uppercase() {
+            if after_lower {
+                field.push('_');
+            }
+            field.push(c.to_ascii_lowercase());
+            after_lower = false;
+        } else if c.is_ascii_alphanumeric() {
+            field.push(c);
+            after_lower = true;
+        } else {
+            field.push('_');
+            after_lower = false;
+        }
+    }
+
+    if field.is_empty() || field == "_" || field.starts_with(|c: char| c.is_ascii_digit()) {
+        field.insert_str(0, "param_");
+    }
+
+    if KEYWORDS.contains(&field.as_str()) {
+        format!("r#{field}")
+    } else if RESERVED.contains(&field.as_str()) {
+        field + "_"
+    } else {
+        field
+    }
+}
+
+/// Drops the `=matcher` part of every bracketed param.
+fn without_matchers(stem: &str) -> String {
+    let mut out = String::with_capacity(stem.len());
+    let mut skipping = false;
+
+    for c in stem.chars() {
+        match c {
+            '=' => skipping = true,
+            ']' => {
+                skipping = false;
+                out.push(c);
+            }
+            c if !skipping => out.push(c),
+            _ => {}
+        }
+    }
+
+    out
+}
+
+fn unique_module(relative: &str, taken: &mut HashSet<String>) -> String {
+    let stem = relative.strip_suffix(".rs").unwrap_or(relative);
+
+    let mut base = String::from("page");
+    for part in stem.split(|c: char| !c.is_ascii_alphanumeric()) {
+        if !part.is_empty() {
+            base.push('_');
+            base.push_str(&part.to_ascii_lowercase());
+        }
+    }
+
+    let mut module = base.clone();
+    let mut n = 2;
+    while !taken.insert(module.clone()) {
+        module = format!("{base}_{n}");
+        n += 1;
+    }
+
+    module
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use super::matcher::{Param, Part, Token, match_parts, score_tokens, tokenize_pattern};
+use super::spec::RouteSpec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// Both routes have the same structure, so the later one can never win.
+    Shadowed,
+    /// The routes score the same and both match some paths, so only their
+    /// insertion order decides which one is rendered.
+    Ambiguous,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteConflict {
+    pub kind: ConflictKind,
+    pub path: String,
+    pub source: String,
+    pub existing_path: String,
+    pub existing_source: String,
+}
+
+impl RouteConflict {
+    /// Compares `spec` against a route that was inserted before it.
+    pub fn between(existing: &RouteSpec, spec: &RouteSpec) -> Option<Self> {
+        let existing_tokens = tokenize_pattern(&existing.path, &existing.params);
+        let tokens = tokenize_pattern(&spec.path, &spec.params);
+
+        let kind = if shape(&existing_tokens) == shape(&tokens) {
+            ConflictKind::Shadowed
+        } else if score_tokens(&existing_tokens) == score_tokens(&tokens)
+            && overlaps(&existing_tokens, &tokens)
+        {
+            ConflictKind::Ambiguous
+        } else {
+            return None;
+        };
+
+        Some(Self::new(kind, existing, spec))
+    }
+
+    pub(crate) fn new(kind: ConflictKind, existing: &RouteSpec, spec: &RouteSpec) -> Self {
+        Self {
+            kind,
+            path: spec.path.clone(),
+            source: spec.source.clone(),
+            existing_path: existing.path.clone(),
+            existing_source: existing.source.clone(),
+        }
+    }
+}
+
+impl fmt::Display for RouteConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let relation = match self.kind {
+            ConflictKind::Shadowed => "is shadowed by",
+            ConflictKind::Ambiguous => "is ambiguous with",
+        };
+
+        write!(
+            f,
+            "{} ({}) {relation} {} ({})",
+            self.path, self.source, self.existing_path, self.existing_source
+        )
+    }
+}
+
+/// Lists every shadowed or ambiguous pair of routes, in insertion order.
+pub fn find_conflicts<'a>(specs: impl IntoIterator<Item = &'a RouteSpec>) -> Vec<RouteConflict> {
+    let specs: Vec<&RouteSpec> = specs.into_iter().collect();
+    let mut conflicts = Vec::new();
+
+    for (index, spec) in specs.iter().enumerate() {
+        for existing in &specs[..index] {
+            if let Some(conflict) = RouteConflict::between(existing, spec) {
+                conflicts.push(conflict);
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// The structure of a route with param names erased. Routes with the same
+/// shape match exactly the same paths; params with different matchers keep
+/// their shapes apart.
+pub(crate) fn shape_of(spec: &RouteSpec) -> Vec<Token> {
+    shape(&tokenize_pattern(&spec.path, &spec.params))
+}
+
+fn shape(tokens: &[Token]) -> Vec<Token> {
+    tokens
+        .iter()
+        .map(|token| match token {
+            Token::ParamSingle(param) => Token::ParamSingle(unnamed(param)),
+            Token::ParamCatchAll(param) => Token::ParamCatchAll(unnamed(param)),
+            Token::ParamOptionalCatchAll(param) => Token::ParamOptionalCatchAll(unnamed(param)),
+            Token::ParamOptional(param) => Token::ParamOptional(unnamed(param)),
+            Token::Mixed(parts) => Token::Mixed(
+                parts
+                    .iter()
+                    .map(|part| match part {
+                        Part::Param(param) => Part::Param(unnamed(param)),
+                        part => part.clone(),
+                    })
+                    .collect(),
+            ),
+            token => token.clone(),
+        })
+        .collect()
+}
+
+fn unnamed(param: &Param) -> Param {
+    Param {
+        name: String::new(),
+        matcher: param.matcher.clone(),
+    }
+}
+
+/// What a token consumes, one segment at a time. `Any` keeps the matcher of
+/// its param, if it has one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step<'a> {
+    Literal(&'a str),
+    Pattern(&'a [Part]),
+    Any(Option<&'a str>),
+    Maybe,
+    Rest,
+}
+
+fn steps(tokens: &[Token]) -> Vec<Step<'_>> {
+    let mut steps = Vec::with_capacity(tokens.len());
+
+    for token in tokens {
+        match token {
+            Token::Static(segment) => steps.push(Step::Literal(segment)),
+            Token::ParamSingle(param) => steps.push(Step::Any(param.matcher.as_deref())),
+            Token::ParamOptional(_) => steps.push(Step::Maybe),
+            Token::Mixed(parts) => steps.push(Step::Pattern(parts)),
+            Token::ParamCatchAll(param) => {
+                steps.push(Step::Any(param.matcher.as_deref()));
+                steps.push(Step::Rest);
+            }
+            Token::ParamOptionalCatchAll(_) | Token::Wildcard => steps.push(Step::Rest),
+        }
+    }
+
+    steps
+}
+
+/// Whether some path is matched by both patterns.
+fn overlaps(a: &[Token], b: &[Token]) -> bool {
+    let a = steps(a);
+    let b = steps(b);
+    let mut memo = HashMap::new();
+
+    intersect(&a, &b, 0, 0, &mut memo)
+}
+
+fn intersect(
+    a: &[Step],
+    b: &[Step],
+    i: usize,
+    j: usize,
+    memo: &mut HashMap<(usize, usize), bool>,
+) -> bool {
+    if let Some(&known) = memo.get(&(i, j)) {
+        return known;
+    }
+
+    let result = match (a.get(i), b.get(j)) {
+        (None, None) => true,
+        (Some(Step::Rest), other) => {
+            intersect(a, b, i + 1, j, memo)
+                || (other.is_some() && intersect(a, b, i, j + 1, memo))
+        }
+        (other, Some(Step::Rest)) => {
+            intersect(a, b, i, j + 1, memo)
+                || (other.is_some() && intersect(a, b, i + 1, j, memo))
+        }
+        (Some(Step::Maybe), other) => {
+            intersect(a, b, i + 1, j, memo)
+                || (other.is_some() && intersect(a, b, i + 1, j + 1, memo))
+        }
+        (other, Some(Step::Maybe)) => {
+            intersect(a, b, i, j + 1, memo)
+                || (other.is_some() && intersect(a, b, i + 1, j + 1, memo))
+        }
+        (Some(x), Some(y)) => {
+            let compatible = match (x, y) {
+                (Step::Literal(x), Step::Literal(y)) => x == y,
+                (Step::Literal(literal), Step::Pattern(parts))
+                | (Step::Pattern(parts), Step::Literal(literal)) => {
+                    match_parts(parts, literal, None, &mut Vec::new())
+                }
+                (Step::Pattern(a), Step::Pattern(b)) => patterns_overlap(a, b),
+                // Differently constrained params are taken to accept
+                // different values.
+                (Step::Any(Some(x)), Step::Any(Some(y))) => x == y,
+                _ => true,
+            };
+            compatible && intersect(a, b, i + 1, j + 1, memo)
+        }
+        (None, Some(_)) | (Some(_), None) => false,
+    };
+
+    memo.insert((i, j), result);
+    result
+}
+
+/// Whether two mixed segments could match the same value, judged by their
+/// leading and trailing text.
+fn patterns_overlap(a: &[Part], b: &[Part]) -> bool {
+    let (a_head, b_head) = (literal(a.first()), literal(b.first()));
+    let (a_tail, b_tail) = (literal(a.last()), literal(b.last()));
+
+    (a_head.starts_with(b_head) || b_head.starts_with(a_head))
+        && (a_tail.ends_with(b_tail) || b_tail.ends_with(a_tail))
+}
+
+fn literal(part: Option<&Part>) -> &str {
+    match part {
+        Some(Part::Literal(literal)) => literal,
+        _ => "",
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt::Write;
+
+use super::errors::DecodeError;
+use super::matcher::split_path;
+
+/// Characters other than ascii letters and digits left as they are in a path
+/// segment: the unreserved and sub-delimiter characters, `:` and `@`.
+const SEGMENT_SAFE: &str = "-._~!$&'()*+,;=:@";
+
+/// Query names and values also escape `&`, `=` and `+`.
+const QUERY_SAFE: &str = "-._~!$'()*,;:@/?";
+
+const FRAGMENT_SAFE: &str = "-._~!$&'()*+,;=:@/?";
+
+/// Percent-encodes a value so it stays a single path segment.
+pub fn encode_segment(value: &str) -> String {
+    encode(value, SEGMENT_SAFE)
+}
+
+pub fn encode_query(value: &str) -> String {
+    encode(value, QUERY_SAFE)
+}
+
+pub fn encode_fragment(value: &str) -> String {
+    encode(value, FRAGMENT_SAFE)
+}
+
+fn encode(value: &str, safe: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || safe.as_bytes().contains(&byte) {
+            out.push(char::from(byte));
+        } else {
+            let _ = write!(out, "%{byte:02X}");
+        }
+    }
+
+    out
+}
+
+/// Decodes `%XX` escapes, failing on a truncated or non-hex escape or when
+/// the decoded bytes are not utf-8.
+pub fn decode(value: &str) -> Result<String, DecodeError> {
+    if !value.contains('%') {
+        return Ok(value.to_string());
+    }
+
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != b'%' {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+
+        let escape = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| DecodeError::MalformedEscape {
+                input: value.to_string(),
+                position: i,
+            })?;
+
+        out.push(escape);
+        i += 3;
+    }
+
+    String::from_utf8(out).map_err(|_| DecodeError::InvalidUtf8 {
+        input: value.to_string(),
+    })
+}
+
+/// Like `decode`, with `+` standing for a space as in html forms.
+pub fn decode_query(value: &str) -> Result<String, DecodeError> {
+    decode(&value.replace('+', " "))
+}
+
+/// Splits a path on `/` before decoding, so an escaped `%2F` stays inside
+/// its segment.
+pub(crate) fn decode_segments(path: &str) -> Result<Vec<String>, DecodeError> {
+    split_path(path).into_iter().map(decode).collect()
+}
@@ -0,0 +1,61 @@
+use std::fmt;
+
+use super::conflict::RouteConflict;
+
+#[derive(Debug)]
+pub enum RouteError {
+    DuplicateRoute(String),
+    DuplicateId(u64),
+    ShadowedRoute(Box<RouteConflict>),
+    InvalidGrammar(String),
+    MultipleFallbacks,
+}
+
+impl fmt::Display for RouteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteError::DuplicateRoute(path) => write!(f, "duplicate route : {path}"),
+            RouteError::DuplicateId(id) => write!(f, "duplicate route id : {id}"),
+            RouteError::ShadowedRoute(conflict) => write!(f, "{conflict}"),
+            RouteError::InvalidGrammar(message) => write!(f, "{message}"),
+            RouteError::MultipleFallbacks => write!(f, "multiple fallback routes"),
+        }
+    }
+}
+
+impl std::error::Error for RouteError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NavigateError {
+    RouteIdNotFound(u64),
+    MissingParam { name: String },
+    /// A param value its matcher rejects.
+    InvalidParam { name: String, matcher: String },
+    UnsupportedWildcard,
+    Cancelled,
+    Deferred,
+    NoRouter,
+    /// Several routers are alive and the caller is not inside any of them.
+    AmbiguousRouter,
+    TooManyRedirects { path: String, limit: usize },
+}
+
+/// A percent-encoded path, query or fragment that could not be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    MalformedEscape { input: String, position: usize },
+    InvalidUtf8 { input: String },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::MalformedEscape { input, position } => {
+                write!(f, "malformed percent escape at {position} : {input}")
+            }
+            DecodeError::InvalidUtf8 { input } => write!(f, "invalid utf-8 after decoding : {input}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
@@ -0,0 +1,103 @@
+use std::fmt;
+
+use super::router::RouteMatch;
+use super::table::RouteTable;
+
+/// How the router got to a route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavigationKind {
+    Push,
+    Replace,
+    Back,
+    Forward,
+}
+
+impl NavigationKind {
+    /// The kind of a history move by `delta` entries.
+    pub fn from_delta(delta: isize) -> Self {
+        if delta < 0 { Self::Back } else { Self::Forward }
+    }
+}
+
+/// Sent after the current route changes. `from` is `None` when the route
+/// being left matched nothing.
+#[derive(Debug, Clone)]
+pub struct RouteChanged {
+    pub from: Option<RouteMatch>,
+    pub to: RouteMatch,
+    pub kind: NavigationKind,
+}
+
+impl RouteChanged {
+    /// Resolves both ends of a navigation, falling back as the router does.
+    /// Returns `None` when `to` matches neither a route nor a fallback.
+    pub fn between<T>(
+        table: &RouteTable<T>,
+        from: &str,
+        to: &str,
+        kind: NavigationKind,
+    ) -> Option<Self> {
+        let (to, _) = table.resolve_or_fallback(to)?;
+        let from = table.resolve_or_fallback(from).map(|(matched, _)| matched);
+
+        Some(Self { from, to, kind })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ListenerId(u64);
+
+pub type RouteListener = Box<dyn FnMut(&RouteChanged)>;
+
+/// Callbacks for route changes, for use without a gpui router.
+#[derive(Default)]
+pub struct RouteListeners {
+    listeners: Vec<(ListenerId, RouteListener)>,
+    next_id: u64,
+}
+
+impl RouteListeners {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe<F>(&mut self, listener: F) -> ListenerId
+    where
+        F: FnMut(&RouteChanged) + 'static,
+    {
+        let id = ListenerId(self.next_id);
+        self.next_id += 1;
+        self.listeners.push((id, Box::new(listener)));
+        id
+    }
+
+    /// Returns false when `id` was not subscribed.
+    pub fn unsubscribe(&mut self, id: ListenerId) -> bool {
+        let len = self.listeners.len();
+        self.listeners.retain(|(listener, _)| *listener != id);
+        self.listeners.len() != len
+    }
+
+    /// Calls every listener with `event`, in the order they subscribed.
+    pub fn emit(&mut self, event: &RouteChanged) {
+        for (_, listener) in &mut self.listeners {
+            listener(event);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.listeners.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+}
+
+impl fmt::Debug for RouteListeners {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouteListeners")
+            .field("len", &self.listeners.len())
+            .finish()
+    }
+}
@@ -0,0 +1,223 @@
+use super::errors::RouteError;
+use super::spec::{ParamKind, ParamSpec, RouteKind, RouteSpec};
+
+pub fn parse_file_path(path: &str, page_dir: Option<&str>) -> Result<RouteSpec, RouteError> {
+    let page_dir = page_dir.unwrap_or("pages");
+
+     let inner = path
+        .strip_prefix(&format!("{}/", page_dir))
+        .ok_or_else(|| RouteError::InvalidGrammar(format!("invalid path : {}", path)))?;
+
+     let inner = inner
+        .strip_suffix(".rs")
+        .ok_or_else(|| RouteError::InvalidGrammar(format!("not a rust file : {}", path)))?;
+
+    let segments: Vec<&str> = inner.split('/').collect();
+    let mut route_path = String::new();
+    let mut params = Vec::new();
+    let mut kind = RouteKind::Static;
+
+    for (index, segment) in segments.iter().enumerate() {
+        if segment.is_empty() {
+            continue;
+        }
+
+        let invalid = || invalid_segment(segment, path);
+
+        if *segment == "index" && index == segments.len() - 1 {
+            // index maps to its directory
+            continue;
+        }
+
+        if *segment == "404" && index == segments.len() - 1 {
+            // fallback for its directory
+            kind = RouteKind::Fallback;
+            route_path.push_str("/*");
+            continue;
+        }
+
+        if *segment == "_layout" && index == segments.len() - 1 {
+            // layout for its directory
+            kind = RouteKind::Layout;
+            route_path.push_str("/*");
+            continue;
+        }
+        
+        if segment.starts_with('(') && segment.ends_with(')') && index < segments.len() - 1 {
+            // route group (name), organizes files without adding to the url
+            continue;
+        }
+
+        if segment.contains(['{', '}']) {
+            // braces mark params in route patterns, so a file cannot use them
+            return Err(invalid());
+        }
+
+        route_path.push('/');
+
+        if segment.contains('[') && !is_bracketed(segment) {
+            // mixed text and params, post-[id] or [name].[ext]
+            route_path.push_str(&parse_mixed(segment, &mut params, path)?);
+            if kind == RouteKind::Static {
+                kind = RouteKind::Dynamic;
+            }
+        }
+
+        else if let Some(name) = segment.strip_prefix("[[...").and_then(|s| s.strip_suffix("]]")) {
+            // optional catchall [[...name]]
+            route_path.push(':');
+            route_path.push_str(name);
+            route_path.push_str("/*?");
+            params.push(param_spec(name, ParamKind::OptionalCatchAll).ok_or_else(invalid)?);
+            kind = RouteKind::CatchAll;
+        }
+
+        else if let Some(name) = segment.strip_prefix("[[").and_then(|s| s.strip_suffix("]]")) {
+            // optional dynamic [[name]]
+            route_path.push(':');
+            route_path.push_str(name);
+            route_path.push('?');
+            params.push(param_spec(name, ParamKind::Optional).ok_or_else(invalid)?);
+            if kind == RouteKind::Static {
+                kind = RouteKind::Dynamic;
+            }
+        }
+
+        else if let Some(name) = segment.strip_prefix("[...").and_then(|s| s.strip_suffix("]")) {
+            // catchall [...name]
+            route_path.push(':');
+            route_path.push_str(name);
+            route_path.push_str("/*");
+            params.push(param_spec(name, ParamKind::CatchAll).ok_or_else(invalid)?);
+            kind = RouteKind::CatchAll;
+        }
+
+        else if let Some(name) = segment.strip_prefix("[").and_then(|s| s.strip_suffix("]")) {
+            // dynamic [name]
+            route_path.push(':');
+            route_path.push_str(name);
+            params.push(param_spec(name, ParamKind::Single).ok_or_else(invalid)?);
+            if kind == RouteKind::Static {
+                kind = RouteKind::Dynamic;
+            }
+        }
+
+        else {
+            // static segment
+            route_path.push_str(segment);
+        }
+    }
+
+    if route_path.is_empty() {
+        route_path.push('/');
+    }
+
+    let id = route_id(inner);
+    let layouts = layout_chain(&segments)
+        .into_iter()
+        .filter(|layout| *layout != id)
+        .collect();
+
+    Ok(RouteSpec {
+        id,
+        path: route_path,
+        kind,
+        params,
+        source: path.to_string(),
+        layouts,
+    })
+}
+
+/// Ids of the `_layout` files in every directory containing the file,
+/// outermost first.
+fn layout_chain(segments: &[&str]) -> Vec<u64> {
+    let dirs = &segments[..segments.len() - 1];
+
+    (0..=dirs.len())
+        .map(|depth| {
+            let mut inner = dirs[..depth].join("/");
+            if !inner.is_empty() {
+                inner.push('/');
+            }
+            inner.push_str("_layout");
+            route_id(&inner)
+        })
+        .collect()
+}
+
+/// Whether the whole segment is a single param such as `[id]` or `[[lang]]`.
+fn is_bracketed(segment: &str) -> bool {
+    let inner = segment
+        .strip_prefix("[[")
+        .and_then(|s| s.strip_suffix("]]"))
+        .or_else(|| segment.strip_prefix('[').and_then(|s| s.strip_suffix(']')));
+
+    inner.is_some_and(|inner| !inner.contains(['[', ']']))
+}
+
+/// Turns `post-[id]` into `post-{id}`, pushing a single param for each
+/// bracket. Catch-all and optional params need a segment of their own.
+fn parse_mixed(segment: &str, params: &mut Vec<ParamSpec>, path: &str) -> Result<String, RouteError> {
+    let invalid = || invalid_segment(segment, path);
+
+    let mut out = String::new();
+    let mut rest = segment;
+
+    while let Some(start) = rest.find('[') {
+        out.push_str(&rest[..start]);
+
+        let after = &rest[start + 1..];
+        let end = after.find(']').ok_or_else(invalid)?;
+        let raw = &after[..end];
+
+        if raw.starts_with(['[', '.']) {
+            return Err(invalid());
+        }
+
+        out.push('{');
+        out.push_str(raw);
+        out.push('}');
+        params.push(param_spec(raw, ParamKind::Single).ok_or_else(invalid)?);
+
+        rest = &after[end + 1..];
+    }
+
+    if rest.contains(']') {
+        return Err(invalid());
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// A param from the inside of its brackets, with an optional `=matcher`.
+/// `None` when the name or the matcher is empty, as in `[]` or `[=integer]`.
+fn param_spec(raw: &str, kind: ParamKind) -> Option<ParamSpec> {
+    let (name, matcher) = match raw.split_once('=') {
+        Some((name, matcher)) => (name, Some(matcher.to_string())),
+        None => (raw, None),
+    };
+
+    if name.is_empty() || matcher.as_deref() == Some("") {
+        return None;
+    }
+
+    Some(ParamSpec {
+        name: name.to_string(),
+        kind,
+        matcher,
+    })
+}
+
+fn invalid_segment(segment: &str, path: &str) -> RouteError {
+    RouteError::InvalidGrammar(format!("invalid segment {segment} : {path}"))
+}
+
+/// A stable id for a page, derived from its path inside the page directory
+/// without the `.rs` extension. FNV-1a is used instead of `DefaultHasher` so
+/// ids stay the same across builds, toolchains and platforms.
+pub fn route_id(inner: &str) -> u64 {
+    inner.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
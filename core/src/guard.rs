@@ -0,0 +1,149 @@
+use std::fmt;
+use std::rc::Rc;
+
+use super::encoding::decode_segments;
+use super::errors::NavigateError;
+use super::location::Location;
+use super::matcher::split_path;
+
+pub const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+/// What a guard wants done with a navigation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuardDecision {
+    Allow,
+    Redirect(String),
+    Cancel,
+}
+
+/// What a page wants done when the router is about to leave it, e.g. with
+/// unsaved changes: let it go, keep it, or hold the navigation until the
+/// user has answered a confirmation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaveDecision {
+    Leave,
+    Stay,
+    Defer,
+}
+
+/// Called with the location being left and the one being navigated to.
+pub type GuardFn = Rc<dyn Fn(&Location, &Location) -> GuardDecision>;
+
+/// Guards run before every navigation, either for all paths or only for
+/// paths under a prefix such as `/admin`.
+#[derive(Clone)]
+pub struct NavigationGuards {
+    guards: Vec<(Option<String>, GuardFn)>,
+    max_redirects: usize,
+}
+
+impl Default for NavigationGuards {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NavigationGuards {
+    pub fn new() -> Self {
+        Self {
+            guards: Vec::new(),
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+        }
+    }
+
+    pub fn before_navigate<F>(&mut self, guard: F)
+    where
+        F: Fn(&Location, &Location) -> GuardDecision + 'static,
+    {
+        self.guards.push((None, Rc::new(guard)));
+    }
+
+    /// Registers `guard` for `prefix` and every path below it, so `/admin`
+    /// (or `/admin/*`) guards `/admin` and `/admin/users` but not `/administer`.
+    pub fn before_navigate_at<F>(&mut self, prefix: impl Into<String>, guard: F)
+    where
+        F: Fn(&Location, &Location) -> GuardDecision + 'static,
+    {
+        let prefix = prefix.into();
+        let prefix = prefix.strip_suffix("/*").unwrap_or(&prefix).to_string();
+        self.guards.push((Some(prefix), Rc::new(guard)));
+    }
+
+    pub fn max_redirects(&self) -> usize {
+        self.max_redirects
+    }
+
+    pub fn set_max_redirects(&mut self, max_redirects: usize) {
+        self.max_redirects = max_redirects;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.guards.is_empty()
+    }
+
+    /// Runs the guards for a navigation from `from` to `to`, in the order they
+    /// were registered, and returns the url to show. A redirect starts the
+    /// guards over for the new url; more than `max_redirects` of them in a
+    /// row is reported as `TooManyRedirects`.
+    pub fn run(&self, from: &str, to: &str) -> Result<String, NavigateError> {
+        let from = Location::parse(from);
+        let mut target = to.to_string();
+        let mut redirects = 0;
+
+        'guards: loop {
+            let to = Location::parse(&target);
+
+            for (prefix, guard) in &self.guards {
+                if prefix.as_deref().is_some_and(|prefix| !has_prefix(&to.path, prefix)) {
+                    continue;
+                }
+
+                match guard(&from, &to) {
+                    GuardDecision::Allow => {}
+                    GuardDecision::Cancel => return Err(NavigateError::Cancelled),
+                    GuardDecision::Redirect(path) => {
+                        redirects += 1;
+                        if redirects > self.max_redirects {
+                            return Err(NavigateError::TooManyRedirects {
+                                path,
+                                limit: self.max_redirects,
+                            });
+                        }
+
+                        target = path;
+                        continue 'guards;
+                    }
+                }
+            }
+
+            return Ok(target);
+        }
+    }
+}
+
+impl fmt::Debug for NavigationGuards {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefixes: Vec<&str> = self
+            .guards
+            .iter()
+            .map(|(prefix, _)| prefix.as_deref().unwrap_or("*"))
+            .collect();
+
+        f.debug_struct("NavigationGuards")
+            .field("prefixes", &prefixes)
+            .field("max_redirects", &self.max_redirects)
+            .finish()
+    }
+}
+
+/// Compares decoded segments, as matching does, so `/%61dmin` is under
+/// `/admin`. A path that fails to decode is guarded by every prefix.
+fn has_prefix(path: &str, prefix: &str) -> bool {
+    let Ok(path) = decode_segments(path) else {
+        return true;
+    };
+    let prefix = split_path(prefix);
+
+    path.len() >= prefix.len()
+        && path.iter().zip(&prefix).all(|(segment, prefix)| segment == prefix)
+}
@@ -0,0 +1,99 @@
+pub const DEFAULT_MAX_DEPTH: usize = 100;
+
+/// A browser style navigation history: a list of visited paths and a cursor
+/// into it. Pushing after going back drops the forward entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct History {
+    entries: Vec<String>,
+    index: usize,
+    max_depth: usize,
+}
+
+impl History {
+    pub fn new(initial: impl Into<String>) -> Self {
+        Self::with_max_depth(initial, DEFAULT_MAX_DEPTH)
+    }
+
+    /// Keeps at most `max_depth` entries, dropping the oldest ones first.
+    pub fn with_max_depth(initial: impl Into<String>, max_depth: usize) -> Self {
+        Self {
+            entries: vec![initial.into()],
+            index: 0,
+            max_depth: max_depth.max(1),
+        }
+    }
+
+    pub fn current(&self) -> &str {
+        &self.entries[self.index]
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth.max(1);
+        self.trim();
+    }
+
+    pub fn push(&mut self, path: impl Into<String>) {
+        self.entries.truncate(self.index + 1);
+        self.entries.push(path.into());
+        self.index = self.entries.len() - 1;
+        self.trim();
+    }
+
+    pub fn replace(&mut self, path: impl Into<String>) {
+        self.entries[self.index] = path.into();
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        self.index > 0
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        self.index + 1 < self.entries.len()
+    }
+
+    pub fn back(&mut self) -> Option<&str> {
+        self.go(-1)
+    }
+
+    pub fn forward(&mut self) -> Option<&str> {
+        self.go(1)
+    }
+
+    /// Moves `delta` entries through the history. Returns `None` and stays
+    /// put when the target is out of range or `delta` is zero.
+    pub fn go(&mut self, delta: isize) -> Option<&str> {
+        let target = self.peek(delta)?;
+        self.index = target;
+        Some(self.current())
+    }
+
+    /// The index `go(delta)` would move to, without moving.
+    pub fn peek(&self, delta: isize) -> Option<usize> {
+        if delta == 0 {
+            return None;
+        }
+
+        let target = self.index.checked_add_signed(delta)?;
+        (target < self.entries.len()).then_some(target)
+    }
+
+    fn trim(&mut self) {
+        let overflow = self.entries.len().saturating_sub(self.max_depth);
+        if overflow > 0 {
+            self.entries.drain(..overflow);
+            self.index = self.index.saturating_sub(overflow);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//! The headless router behind `fs-router`, shared with the `routes!` macro.
+//! Use it through `fs_router::core`.
+
+pub mod spec;
+pub mod grammar;
+pub mod table;
+pub mod errors;
+pub mod scan;
+pub mod matcher;
+pub mod router;
+pub mod conflict;
+pub mod history;
+pub mod param_matcher;
+pub mod location;
+pub mod encoding;
+pub mod guard;
+pub mod events;
+pub mod navigator;
+
+pub mod build;
+
+#[cfg(feature = "serde")]
+pub mod params;
+
+pub use spec::*;
+pub use table::*;
+pub use router::*;
+pub use location::*;
@@ -0,0 +1,99 @@
+use std::fmt;
+
+use super::encoding::{decode, decode_query, encode_fragment, encode_query};
+
+/// A url split into the path used for matching, its query pairs and its
+/// fragment, e.g. `/search?q=rust#top`. The path stays percent-encoded until
+/// it is split into segments; query pairs and the fragment are decoded, and
+/// a value that fails to decode is kept as written.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Location {
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub fragment: Option<String>,
+}
+
+impl Location {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn parse(input: &str) -> Self {
+        let (rest, fragment) = match input.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(decode_or_raw(fragment, decode))),
+            None => (input, None),
+        };
+
+        let (path, query) = match rest.split_once('?') {
+            Some((path, query)) => (path, parse_query(query)),
+            None => (rest, Vec::new()),
+        };
+
+        Self {
+            path: path.to_string(),
+            query,
+            fragment,
+        }
+    }
+
+    /// The first value of the query param `name`.
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn with_query(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_fragment(mut self, fragment: impl Into<String>) -> Self {
+        self.fragment = Some(fragment.into());
+        self
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path)?;
+
+        for (index, (name, value)) in self.query.iter().enumerate() {
+            let separator = if index == 0 { '?' } else { '&' };
+            let name = encode_query(name);
+            if value.is_empty() {
+                write!(f, "{separator}{name}")?;
+            } else {
+                write!(f, "{separator}{name}={}", encode_query(value))?;
+            }
+        }
+
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{}", encode_fragment(fragment))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((name, value)) => (
+                decode_or_raw(name, decode_query),
+                decode_or_raw(value, decode_query),
+            ),
+            None => (decode_or_raw(pair, decode_query), String::new()),
+        })
+        .collect()
+}
+
+fn decode_or_raw<E>(value: &str, decode: impl Fn(&str) -> Result<String, E>) -> String {
+    decode(value).unwrap_or_else(|_| value.to_string())
+}
@@ -0,0 +1,468 @@
+use std::collections::HashMap;
+
+use super::encoding::decode_segments;
+use super::param_matcher::ParamMatchers;
+use super::spec::{ParamKind, ParamSpec, RouteSpec};
+use super::table::RouteTable;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Token {
+    Static(String),
+    ParamSingle(Param),
+    ParamCatchAll(Param),
+    ParamOptionalCatchAll(Param),
+    ParamOptional(Param),
+    /// A segment mixing text and params, such as `post-{id}`.
+    Mixed(Vec<Part>),
+    Wildcard,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Part {
+    Literal(String),
+    Param(Param),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Param {
+    pub(crate) name: String,
+    pub(crate) matcher: Option<String>,
+}
+
+/// Scores are scaled so each optional token can cost a point without
+/// reordering routes that differ in their required tokens.
+const SCORE_SCALE: usize = 16;
+
+pub(crate) fn tokenize_pattern(pattern: &str, params: &[ParamSpec]) -> Vec<Token> {
+    let segments = split_path(pattern);
+
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < segments.len() {
+        let seg = segments[i];
+
+        if seg == "*" {
+            tokens.push(Token::Wildcard);
+            i += 1;
+            continue;
+        }
+
+        // Braces only mark a mixed segment when every one of them names a
+        // declared single param; otherwise the segment is static text.
+        if seg.contains('{') {
+            let parts = parse_parts(seg);
+            let declared = parts.iter().all(|part| match part {
+                Part::Param(param) => params
+                    .iter()
+                    .any(|p| p.name == param.name && p.kind == ParamKind::Single),
+                Part::Literal(_) => true,
+            });
+
+            if declared {
+                tokens.push(Token::Mixed(parts));
+                i += 1;
+                continue;
+            }
+        }
+
+        if let Some(raw) = seg.strip_prefix(':') {
+            let (raw, optional) = match raw.strip_suffix('?') {
+                Some(raw) => (raw, true),
+                None => (raw, false),
+            };
+
+            let param = parse_param(raw);
+            let name = param.name.as_str();
+
+            let kind = params.iter().find(|p| p.name == name).map(|p| &p.kind);
+            let next = segments.get(i + 1).copied();
+
+            match (kind, next) {
+                (Some(ParamKind::Optional), _) if optional => {
+                    tokens.push(Token::ParamOptional(param));
+                    i += 1;
+                }
+                (Some(ParamKind::CatchAll), Some("*")) => {
+                    tokens.push(Token::ParamCatchAll(param));
+                    i += 2;
+                }
+                (Some(ParamKind::OptionalCatchAll), Some("*?")) => {
+                    tokens.push(Token::ParamOptionalCatchAll(param));
+                    i += 2;
+                }
+                _ => {
+                    tokens.push(Token::ParamSingle(param));
+                    i += 1;
+                }
+            }
+            continue;
+        }
+
+        tokens.push(Token::Static(seg.to_string()));
+        i += 1;
+    }
+
+    tokens
+}
+
+fn parse_param(raw: &str) -> Param {
+    let (name, matcher) = match raw.split_once('=') {
+        Some((name, matcher)) => (name, Some(matcher.to_string())),
+        None => (raw, None),
+    };
+
+    Param {
+        name: name.to_string(),
+        matcher,
+    }
+}
+
+fn parse_parts(segment: &str) -> Vec<Part> {
+    let mut parts = Vec::new();
+    let mut rest = segment;
+
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            parts.push(Part::Literal(rest[..start].to_string()));
+        }
+
+        let after = &rest[start + 1..];
+        let end = after.find('}').unwrap_or(after.len());
+        parts.push(Part::Param(parse_param(&after[..end])));
+        rest = after.get(end + 1..).unwrap_or_default();
+    }
+
+    if !rest.is_empty() {
+        parts.push(Part::Literal(rest.to_string()));
+    }
+
+    parts
+}
+
+/// Optional tokens add nothing and cost a point, so a route ranks below both
+/// the same route with the optional part required and the one without it.
+/// A constrained param ranks above the same param without a matcher, and a
+/// mixed segment ranks between a static segment and a plain param.
+pub(crate) fn score_tokens(tokens: &[Token]) -> usize {
+    let mut required = 0;
+    let mut optional = 0;
+
+    for token in tokens {
+        match token {
+            Token::Static(_) => required += 100 + 1,
+            Token::ParamSingle(param) => required += 10 + constraint(param) + 1,
+            Token::ParamCatchAll(param) => required += 1 + constraint(param) + 1,
+            Token::Mixed(parts) => {
+                let constraints: usize = parts
+                    .iter()
+                    .map(|part| match part {
+                        Part::Param(param) => constraint(param),
+                        Part::Literal(_) => 0,
+                    })
+                    .sum();
+                required += 50 + constraints + 1;
+            }
+            Token::Wildcard => required += 1,
+            Token::ParamOptionalCatchAll(_) | Token::ParamOptional(_) => optional += 1,
+        }
+    }
+
+    ((required + 1) * SCORE_SCALE).saturating_sub(optional)
+}
+
+fn constraint(param: &Param) -> usize {
+    if param.matcher.is_some() { 5 } else { 0 }
+}
+
+pub(crate) fn split_path(path: &str) -> Vec<&str> {
+    path.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+/// Every way `token` can consume segments starting at `index`, in the order
+/// they should be tried: the index after the token and the params captured.
+/// Every segment a constrained param captures must satisfy its matcher.
+pub(crate) fn advance(
+    token: &Token,
+    segments: &[&str],
+    index: usize,
+    matchers: &ParamMatchers,
+) -> Vec<(usize, Vec<(String, String)>)> {
+    if let Token::Mixed(parts) = token {
+        let mut captured = Vec::new();
+        return match segments.get(index) {
+            Some(segment) if match_parts(parts, segment, Some(matchers), &mut captured) => {
+                vec![(index + 1, captured)]
+            }
+            _ => Vec::new(),
+        };
+    }
+
+    let mut steps: Vec<(usize, Option<String>)> = match token {
+        Token::Static(expected) => match segments.get(index) {
+            Some(actual) if actual == expected => vec![(index + 1, None)],
+            _ => Vec::new(),
+        },
+        Token::ParamSingle(_) => match segments.get(index) {
+            Some(value) => vec![(index + 1, Some(value.to_string()))],
+            None => Vec::new(),
+        },
+        Token::ParamCatchAll(_) => (index + 1..=segments.len())
+            .rev()
+            .map(|end| (end, Some(segments[index..end].join("/"))))
+            .collect(),
+        Token::ParamOptional(_) => match segments.get(index) {
+            Some(value) => vec![(index + 1, Some(value.to_string())), (index, None)],
+            None => vec![(index, None)],
+        },
+        Token::ParamOptionalCatchAll(_) => (index..=segments.len())
+            .rev()
+            .map(|end| (end, (end > index).then(|| segments[index..end].join("/"))))
+            .collect(),
+        Token::Wildcard | Token::Mixed(_) => {
+            (index..=segments.len()).map(|end| (end, None)).collect()
+        }
+    };
+
+    let Some(param) = token_param(token) else {
+        return steps.into_iter().map(|(end, _)| (end, Vec::new())).collect();
+    };
+
+    if let Some(matcher) = param.matcher.as_deref() {
+        steps.retain(|(end, _)| {
+            segments[index..*end]
+                .iter()
+                .all(|segment| matchers.matches(matcher, segment))
+        });
+    }
+
+    steps
+        .into_iter()
+        .map(|(end, value)| {
+            let captured = value
+                .map(|value| vec![(param.name.clone(), value)])
+                .unwrap_or_default();
+            (end, captured)
+        })
+        .collect()
+}
+
+/// Matches one segment against mixed parts, trying the longest value for
+/// each param first. Matchers are skipped when `matchers` is `None`.
+pub(crate) fn match_parts(
+    parts: &[Part],
+    value: &str,
+    matchers: Option<&ParamMatchers>,
+    captured: &mut Vec<(String, String)>,
+) -> bool {
+    match parts.split_first() {
+        None => value.is_empty(),
+        Some((Part::Literal(literal), rest)) => value
+            .strip_prefix(literal.as_str())
+            .is_some_and(|value| match_parts(rest, value, matchers, captured)),
+        Some((Part::Param(param), rest)) => {
+            for end in (1..=value.len()).rev() {
+                if !value.is_char_boundary(end) {
+                    continue;
+                }
+
+                let (head, tail) = value.split_at(end);
+                if let (Some(matchers), Some(matcher)) = (matchers, param.matcher.as_deref())
+                    && !matchers.matches(matcher, head)
+                {
+                    continue;
+                }
+
+                captured.push((param.name.clone(), head.to_string()));
+                if match_parts(rest, tail, matchers, captured) {
+                    return true;
+                }
+                captured.pop();
+            }
+
+            false
+        }
+    }
+}
+
+pub(crate) fn token_param(token: &Token) -> Option<&Param> {
+    match token {
+        Token::ParamSingle(param)
+        | Token::ParamCatchAll(param)
+        | Token::ParamOptionalCatchAll(param)
+        | Token::ParamOptional(param) => Some(param),
+        Token::Static(_) | Token::Mixed(_) | Token::Wildcard => None,
+    }
+}
+
+struct Candidate {
+    route: usize,
+    score: usize,
+    params: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    statics: HashMap<String, usize>,
+    dynamic: Vec<(Token, usize)>,
+    terminal: Vec<usize>,
+    best_score: usize,
+}
+
+/// A segment trie compiled from the routes of a [`RouteTable`].
+///
+/// Static segments are looked up by hash, so resolving a path only walks the
+/// branches that can still match it. When several routes match, the highest
+/// scoring one wins and ties go to the route inserted first.
+#[derive(Debug, Clone)]
+pub struct RouteMatcher {
+    nodes: Vec<Node>,
+    scores: Vec<usize>,
+    matchers: ParamMatchers,
+}
+
+impl RouteMatcher {
+    pub fn new<T>(table: &RouteTable<T>) -> Self {
+        Self::with_matchers(
+            table.routes().iter().map(|(spec, _)| spec),
+            table.matchers().clone(),
+        )
+    }
+
+    /// Compiles `specs` with the built-in param matchers.
+    pub fn from_specs<'a>(specs: impl IntoIterator<Item = &'a RouteSpec>) -> Self {
+        Self::with_matchers(specs, ParamMatchers::builtin().clone())
+    }
+
+    pub fn with_matchers<'a>(
+        specs: impl IntoIterator<Item = &'a RouteSpec>,
+        matchers: ParamMatchers,
+    ) -> Self {
+        let mut matcher = Self {
+            nodes: vec![Node::default()],
+            scores: Vec::new(),
+            matchers,
+        };
+
+        for (route, spec) in specs.into_iter().enumerate() {
+            let tokens = tokenize_pattern(&spec.path, &spec.params);
+            let score = score_tokens(&tokens);
+            matcher.scores.push(score);
+
+            let mut node = 0;
+            matcher.nodes[node].best_score = matcher.nodes[node].best_score.max(score);
+
+            for token in tokens {
+                node = matcher.child(node, token);
+                matcher.nodes[node].best_score = matcher.nodes[node].best_score.max(score);
+            }
+
+            matcher.nodes[node].terminal.push(route);
+        }
+
+        matcher
+    }
+
+    fn child(&mut self, node: usize, token: Token) -> usize {
+        let existing = match &token {
+            Token::Static(segment) => self.nodes[node].statics.get(segment).copied(),
+            _ => self.nodes[node]
+                .dynamic
+                .iter()
+                .find(|(t, _)| *t == token)
+                .map(|(_, child)| *child),
+        };
+
+        if let Some(child) = existing {
+            return child;
+        }
+
+        let child = self.nodes.len();
+        self.nodes.push(Node::default());
+
+        match token {
+            Token::Static(segment) => {
+                self.nodes[node].statics.insert(segment, child);
+            }
+            token => self.nodes[node].dynamic.push((token, child)),
+        }
+
+        child
+    }
+
+    /// Returns the index of the winning route in the table it was compiled
+    /// from, along with the params captured for it. Segments are decoded
+    /// before matching, and a path that fails to decode matches nothing.
+    pub fn resolve(&self, path: &str) -> Option<(usize, Vec<(String, String)>)> {
+        let segments = decode_segments(path).ok()?;
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+        self.resolve_segments(&segments)
+    }
+
+    pub(crate) fn resolve_segments(
+        &self,
+        segments: &[&str],
+    ) -> Option<(usize, Vec<(String, String)>)> {
+        let mut best = None;
+        let mut params = Vec::new();
+
+        self.walk(0, segments, 0, &mut params, &mut best);
+
+        best.map(|candidate| (candidate.route, candidate.params))
+    }
+
+    fn walk(
+        &self,
+        node: usize,
+        segments: &[&str],
+        index: usize,
+        params: &mut Vec<(String, String)>,
+        best: &mut Option<Candidate>,
+    ) {
+        let current = &self.nodes[node];
+
+        if let Some(candidate) = best
+            && current.best_score < candidate.score
+        {
+            return;
+        }
+
+        if index == segments.len() {
+            for &route in &current.terminal {
+                let score = self.scores[route];
+                let better = match best {
+                    Some(candidate) => {
+                        score > candidate.score
+                            || (score == candidate.score && route < candidate.route)
+                    }
+                    None => true,
+                };
+
+                if better {
+                    *best = Some(Candidate {
+                        route,
+                        score,
+                        params: params.clone(),
+                    });
+                }
+            }
+        }
+
+        if let Some(segment) = segments.get(index)
+            && let Some(&child) = current.statics.get(*segment)
+        {
+            self.walk(child, segments, index + 1, params, best);
+        }
+
+        for (token, child) in &current.dynamic {
+            for (next, captured) in advance(token, segments, index, &self.matchers) {
+                let len = params.len();
+                params.extend(captured);
+
+                self.walk(*child, segments, next, params, best);
+
+                params.truncate(len);
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use super::errors::NavigateError;
+use super::events::{ListenerId, NavigationKind, RouteChanged, RouteListeners};
+use super::guard::{GuardDecision, NavigationGuards};
+use super::history::History;
+use super::location::Location;
+use super::router::RouteMatch;
+use super::table::RouteTable;
+
+/// How a navigation moves through the history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    Push,
+    Replace,
+    Go(isize),
+}
+
+/// The router without a view: a table, a history and navigation guards, with
+/// listeners told about every route change. `RouterView` wraps one and adds
+/// leave hooks and rendering on top of gpui.
+#[derive(Debug)]
+pub struct Navigator<T> {
+    table: RouteTable<T>,
+    history: History,
+    guards: NavigationGuards,
+    listeners: RouteListeners,
+}
+
+impl<T> Navigator<T> {
+    pub fn new(table: RouteTable<T>, initial_route: impl Into<String>) -> Self {
+        Self {
+            table,
+            history: History::new(initial_route),
+            guards: NavigationGuards::new(),
+            listeners: RouteListeners::new(),
+        }
+    }
+
+    /// Keeps at most `max_depth` history entries.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.history.set_max_depth(max_depth);
+        self
+    }
+
+    /// Gives up with `TooManyRedirects` after `max_redirects` guard redirects
+    /// in a single navigation.
+    pub fn with_max_redirects(mut self, max_redirects: usize) -> Self {
+        self.guards.set_max_redirects(max_redirects);
+        self
+    }
+
+    pub fn table(&self) -> &RouteTable<T> {
+        &self.table
+    }
+
+    pub fn table_mut(&mut self) -> &mut RouteTable<T> {
+        &mut self.table
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn guards(&self) -> &NavigationGuards {
+        &self.guards
+    }
+
+    pub fn guards_mut(&mut self) -> &mut NavigationGuards {
+        &mut self.guards
+    }
+
+    pub fn before_navigate<F>(&mut self, guard: F)
+    where
+        F: Fn(&Location, &Location) -> GuardDecision + 'static,
+    {
+        self.guards.before_navigate(guard);
+    }
+
+    pub fn before_navigate_at<F>(&mut self, prefix: impl Into<String>, guard: F)
+    where
+        F: Fn(&Location, &Location) -> GuardDecision + 'static,
+    {
+        self.guards.before_navigate_at(prefix, guard);
+    }
+
+    /// Calls `listener` after every navigation that lands on a route or
+    /// fallback.
+    pub fn subscribe<F>(&mut self, listener: F) -> ListenerId
+    where
+        F: FnMut(&RouteChanged) + 'static,
+    {
+        self.listeners.subscribe(listener)
+    }
+
+    pub fn unsubscribe(&mut self, id: ListenerId) -> bool {
+        self.listeners.unsubscribe(id)
+    }
+
+    pub fn route(&self) -> &str {
+        self.history.current()
+    }
+
+    /// The route or fallback `route()` resolves to, with its handler.
+    pub fn current(&self) -> Option<(RouteMatch, &T)> {
+        self.table.resolve_or_fallback(self.route())
+    }
+
+    /// Pushes `route`, or the url a guard redirected it to, onto the history.
+    pub fn navigate(&mut self, route: impl Into<String>) -> Result<(), NavigateError> {
+        self.commit(Transition::Push, &route.into())?;
+        Ok(())
+    }
+
+    /// Swaps the current history entry for `route`.
+    pub fn replace(&mut self, route: impl Into<String>) -> Result<(), NavigateError> {
+        self.commit(Transition::Replace, &route.into())?;
+        Ok(())
+    }
+
+    pub fn navigate_by_id(
+        &mut self,
+        route_id: u64,
+        params: &[(&str, &str)],
+    ) -> Result<(), NavigateError> {
+        let route = self.table.path_for(route_id, params)?;
+        self.navigate(route)
+    }
+
+    pub fn back(&mut self) -> Result<bool, NavigateError> {
+        self.go(-1)
+    }
+
+    pub fn forward(&mut self) -> Result<bool, NavigateError> {
+        self.go(1)
+    }
+
+    /// Moves `delta` entries through the history. Returns false when there
+    /// is no such entry.
+    pub fn go(&mut self, delta: isize) -> Result<bool, NavigateError> {
+        let Some(index) = self.history.peek(delta) else {
+            return Ok(false);
+        };
+
+        let route = self.history.entries()[index].clone();
+        self.commit(Transition::Go(delta), &route)?;
+        Ok(true)
+    }
+
+    /// Runs the guards for a navigation to `route`, applies `transition` to
+    /// the history and tells the listeners. A redirect during a history move
+    /// replaces the entry moved to, and a cancel leaves the history as it
+    /// was. Returns the change, or `None` when the new route matches neither
+    /// a route nor a fallback.
+    pub fn commit(
+        &mut self,
+        transition: Transition,
+        route: &str,
+    ) -> Result<Option<RouteChanged>, NavigateError> {
+        let route = self.guards.run(self.route(), route)?;
+        let from = self.route().to_string();
+
+        let kind = match transition {
+            Transition::Push => {
+                self.history.push(route);
+                NavigationKind::Push
+            }
+            Transition::Replace => {
+                self.history.replace(route);
+                NavigationKind::Replace
+            }
+            Transition::Go(delta) => {
+                if self.history.go(delta) != Some(route.as_str()) {
+                    self.history.replace(route);
+                }
+                NavigationKind::from_delta(delta)
+            }
+        };
+
+        let changed = RouteChanged::between(&self.table, &from, self.history.current(), kind);
+        if let Some(changed) = &changed {
+            self.listeners.emit(changed);
+        }
+
+        Ok(changed)
+    }
+}
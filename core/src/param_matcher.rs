@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+pub type ParamMatcherFn = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Named checks for constrained params such as `[id=integer]`. A param whose
+/// matcher is not registered never matches.
+#[derive(Clone)]
+pub struct ParamMatchers {
+    matchers: HashMap<String, ParamMatcherFn>,
+}
+
+impl Default for ParamMatchers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParamMatchers {
+    /// A registry with the built-in `integer`, `uuid` and `slug` matchers.
+    pub fn new() -> Self {
+        let mut matchers = Self {
+            matchers: HashMap::new(),
+        };
+
+        matchers.register("integer", is_integer);
+        matchers.register("uuid", is_uuid);
+        matchers.register("slug", is_slug);
+        matchers
+    }
+
+    /// The built-in registry, shared by lookups that have no table.
+    pub fn builtin() -> &'static Self {
+        static BUILTIN: OnceLock<ParamMatchers> = OnceLock::new();
+        BUILTIN.get_or_init(Self::new)
+    }
+
+    /// Registers `matcher` under `name`, replacing any matcher with that name.
+    pub fn register<F>(&mut self, name: impl Into<String>, matcher: F)
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.matchers.insert(name.into(), Arc::new(matcher));
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.matchers.contains_key(name)
+    }
+
+    pub fn matches(&self, name: &str, value: &str) -> bool {
+        self.matchers
+            .get(name)
+            .is_some_and(|matcher| matcher(value))
+    }
+}
+
+impl fmt::Debug for ParamMatchers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&str> = self.matchers.keys().map(String::as_str).collect();
+        names.sort_unstable();
+
+        f.debug_struct("ParamMatchers")
+            .field("names", &names)
+            .finish()
+    }
+}
+
+/// An optionally negative run of ascii digits.
+pub fn is_integer(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    !digits.is_empty() && digits.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+/// A hyphenated uuid such as `67e55044-10b1-426f-9247-bb680e5fe0c8`, in
+/// either case.
+pub fn is_uuid(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+
+    groups.len() == 5
+        && groups.iter().zip([8, 4, 4, 4, 12]).all(|(group, len)| {
+            group.len() == len && group.bytes().all(|byte| byte.is_ascii_hexdigit())
+        })
+}
+
+/// Lowercase ascii letters and digits in words joined by single hyphens.
+pub fn is_slug(value: &str) -> bool {
+    !value.is_empty()
+        && value.split('-').all(|word| {
+            !word.is_empty()
+                && word
+                    .bytes()
+                    .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit())
+        })
+}
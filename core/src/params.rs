@@ -0,0 +1,218 @@
+//! Typed params through serde, behind the `serde` feature.
+
+use std::fmt;
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess,
+    Visitor,
+};
+
+use crate::RouteMatch;
+
+/// A param that could not be deserialized. `param` names it when the error
+/// belongs to a single param rather than the whole struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamsError {
+    pub param: Option<String>,
+    pub message: String,
+}
+
+impl ParamsError {
+    fn in_param(mut self, name: &str) -> Self {
+        self.param.get_or_insert_with(|| name.to_string());
+        self
+    }
+}
+
+impl fmt::Display for ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.param {
+            Some(param) => write!(f, "invalid param {param} : {}", self.message),
+            None => write!(f, "invalid params : {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParamsError {}
+
+impl de::Error for ParamsError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        Self {
+            param: None,
+            message: message.to_string(),
+        }
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Self {
+            param: Some(field.to_string()),
+            message: "missing".to_string(),
+        }
+    }
+}
+
+impl RouteMatch {
+    /// Deserializes the params into `T`, e.g. a struct with a field per
+    /// param. Values are parsed into numbers, bools and unit enum variants,
+    /// and catch-all values split on `/` into sequences such as `Vec<String>`.
+    pub fn params_as<T: DeserializeOwned>(&self) -> Result<T, ParamsError> {
+        T::deserialize(ParamsDeserializer {
+            params: &self.params,
+        })
+    }
+}
+
+struct ParamsDeserializer<'a> {
+    params: &'a [(String, String)],
+}
+
+impl<'de> Deserializer<'de> for ParamsDeserializer<'_> {
+    type Error = ParamsError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamsError> {
+        visitor.visit_map(ParamsAccess {
+            params: self.params.iter(),
+            value: None,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct ParamsAccess<'a> {
+    params: std::slice::Iter<'a, (String, String)>,
+    value: Option<&'a (String, String)>,
+}
+
+impl<'de> MapAccess<'de> for ParamsAccess<'_> {
+    type Error = ParamsError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, ParamsError> {
+        let Some(param) = self.params.next() else {
+            return Ok(None);
+        };
+
+        self.value = Some(param);
+        seed.deserialize(param.0.as_str().into_deserializer())
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, ParamsError> {
+        let (name, value) = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before key"))?;
+
+        seed.deserialize(ValueDeserializer { value })
+            .map_err(|error| error.in_param(name))
+    }
+}
+
+/// A single param value, parsed on demand into whatever type is asked for.
+struct ValueDeserializer<'a> {
+    value: &'a str,
+}
+
+impl ValueDeserializer<'_> {
+    fn parse<T: std::str::FromStr>(&self, expected: &str) -> Result<T, ParamsError> {
+        self.value
+            .parse()
+            .map_err(|_| de::Error::custom(format!("expected {expected}, found {:?}", self.value)))
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident : $ty:ty),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamsError> {
+                visitor.$visit(self.parse::<$ty>(stringify!($ty))?)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'_> {
+    type Error = ParamsError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamsError> {
+        visitor.visit_str(self.value)
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool: bool,
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_i128 => visit_i128: i128,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_u128 => visit_u128: u128,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64,
+        deserialize_char => visit_char: char,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamsError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ParamsError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Catch-all values hold their segments joined with `/`.
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamsError> {
+        visitor.visit_seq(SegmentsAccess {
+            segments: self.value.split('/').filter(|segment| !segment.is_empty()),
+        })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ParamsError> {
+        visitor.visit_enum(self.value.into_deserializer())
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct SegmentsAccess<I> {
+    segments: I,
+}
+
+impl<'de, 'a, I> SeqAccess<'de> for SegmentsAccess<I>
+where
+    I: Iterator<Item = &'a str>,
+{
+    type Error = ParamsError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, ParamsError> {
+        self.segments
+            .next()
+            .map(|value| seed.deserialize(ValueDeserializer { value }))
+            .transpose()
+    }
+}
@@ -0,0 +1,325 @@
+use super::encoding::{decode_segments, encode_segment};
+use super::errors::{DecodeError, NavigateError};
+use super::location::Location;
+use super::matcher::{Param, Part, Token, advance, score_tokens, split_path, tokenize_pattern};
+use super::param_matcher::ParamMatchers;
+use super::spec::{ParamKind, RouteSpec};
+use super::table::RouteTable;
+
+/// A route matched against a url. `location` is the whole url, with the
+/// query pairs and fragment that took no part in matching.
+#[derive(Debug, Clone)]
+pub struct RouteMatch {
+    pub spec: RouteSpec,
+    pub params: Vec<(String, String)>,
+    pub location: Location,
+}
+
+impl RouteMatch {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.location.query_param(name)
+    }
+
+    fn at(spec: &RouteSpec, params: Vec<(String, String)>, location: Location) -> Self {
+        Self {
+            spec: spec.clone(),
+            params,
+            location,
+        }
+    }
+}
+
+impl<T> RouteTable<T> {
+    /// Resolves `path` against the compiled matcher, which is built on first
+    /// use and reused until the table changes. Any query and fragment are
+    /// left out of matching and kept on the match. A path that is not
+    /// validly percent-encoded matches nothing; see `try_resolve`.
+    pub fn resolve(&self, path: &str) -> Option<RouteMatch> {
+        self.resolve_handler(path).map(|(matched, _)| matched)
+    }
+
+    pub fn resolve_handler(&self, path: &str) -> Option<(RouteMatch, &T)> {
+        self.try_resolve_handler(path).ok().flatten()
+    }
+
+    /// Like `resolve`, but reports a malformed escape or invalid utf-8 in the
+    /// path instead of treating it as a miss. The query and fragment never
+    /// fail; values that do not decode are kept as written.
+    pub fn try_resolve(&self, path: &str) -> Result<Option<RouteMatch>, DecodeError> {
+        Ok(self.try_resolve_handler(path)?.map(|(matched, _)| matched))
+    }
+
+    pub fn try_resolve_handler(&self, path: &str) -> Result<Option<(RouteMatch, &T)>, DecodeError> {
+        let location = Location::parse(path);
+        let segments = decode_segments(&location.path)?;
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+        let Some((index, params)) = self.matcher().resolve_segments(&segments) else {
+            return Ok(None);
+        };
+        let Some((spec, handler)) = self.routes().get(index) else {
+            return Ok(None);
+        };
+
+        Ok(Some((RouteMatch::at(spec, params, location), handler)))
+    }
+
+    /// The fallback of the deepest directory that contains `path`. A path
+    /// that fails to decode is matched as written, so it still gets a 404.
+    pub fn fallback_for(&self, path: &str) -> Option<(RouteMatch, &T)> {
+        let location = Location::parse(path);
+        let segments = decode_segments(&location.path).unwrap_or_else(|_| {
+            split_path(&location.path)
+                .into_iter()
+                .map(str::to_string)
+                .collect()
+        });
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+        let mut best: Option<(usize, RouteMatch, &T)> = None;
+
+        for (spec, handler) in self.fallbacks() {
+            let Some(params) = match_segments(spec, &segments, self.matchers()) else {
+                continue;
+            };
+
+            let score = score_spec(spec);
+            match &best {
+                Some((best_score, _, _)) if *best_score >= score => {}
+                _ => best = Some((score, RouteMatch::at(spec, params, location.clone()), handler)),
+            }
+        }
+
+        best.map(|(_, matched, handler)| (matched, handler))
+    }
+
+    /// Resolves `path` to a route, or to its nearest fallback when no route
+    /// matches.
+    pub fn resolve_or_fallback(&self, path: &str) -> Option<(RouteMatch, &T)> {
+        self.resolve_handler(path)
+            .or_else(|| self.fallback_for(path))
+    }
+
+    pub fn path_for(
+        &self,
+        route_id: u64,
+        params: &[(&str, &str)],
+    ) -> Result<String, NavigateError> {
+        let (spec, _) = self
+            .routes()
+            .iter()
+            .find(|(spec, _)| spec.id == route_id)
+            .ok_or(NavigateError::RouteIdNotFound(route_id))?;
+
+        build_path(spec, params)
+    }
+}
+
+pub fn score_spec(spec: &RouteSpec) -> usize {
+    score_tokens(&tokenize_pattern(&spec.path, &spec.params))
+}
+
+/// Builds the path of `spec` from `params`, percent-encoding each value so
+/// it survives a round trip through `match_route`. Catch-all values are
+/// split on `/` and each of their segments encoded.
+pub fn build_path(spec: &RouteSpec, params: &[(&str, &str)]) -> Result<String, NavigateError> {
+    let tokens = tokenize_pattern(&spec.path, &spec.params);
+
+    let mut result = String::new();
+    for token in tokens {
+        match token {
+            Token::Static(s) => {
+                result.push('/');
+                result.push_str(&encode_segment(&s));
+            }
+            Token::ParamSingle(Param { name, .. }) => {
+                let value = param_value(params, &name)
+                    .ok_or(NavigateError::MissingParam { name })?;
+
+                result.push('/');
+                result.push_str(&encode_segment(value));
+            }
+            Token::ParamCatchAll(Param { name, .. }) => {
+                let value = param_value(params, &name)
+                    .ok_or(NavigateError::MissingParam { name })?;
+
+                push_segments(&mut result, value);
+            }
+            Token::ParamOptional(Param { name, .. }) => {
+                let value = param_value(params, &name).unwrap_or_default();
+
+                if !value.is_empty() {
+                    result.push('/');
+                    result.push_str(&encode_segment(value));
+                }
+            }
+            Token::ParamOptionalCatchAll(Param { name, .. }) => {
+                let value = param_value(params, &name).unwrap_or_default();
+
+                push_segments(&mut result, value);
+            }
+            Token::Mixed(parts) => {
+                result.push('/');
+                for part in parts {
+                    match part {
+                        Part::Literal(literal) => result.push_str(&encode_segment(&literal)),
+                        Part::Param(Param { name, .. }) => {
+                            let value = param_value(params, &name)
+                                .ok_or(NavigateError::MissingParam { name })?;
+
+                            result.push_str(&encode_segment(value));
+                        }
+                    }
+                }
+            }
+            Token::Wildcard => return Err(NavigateError::UnsupportedWildcard),
+        }
+    }
+
+    if result.is_empty() {
+        result.push('/');
+    }
+
+    Ok(result)
+}
+
+fn param_value<'a>(params: &[(&str, &'a str)], name: &str) -> Option<&'a str> {
+    params.iter().find(|(k, _)| *k == name).map(|(_, v)| *v)
+}
+
+fn push_segments(result: &mut String, value: &str) {
+    for segment in value.split('/').filter(|segment| !segment.is_empty()) {
+        result.push('/');
+        result.push_str(&encode_segment(segment));
+    }
+}
+
+/// Like `build_path`, but first checks every constrained param against
+/// `matchers`, so the path it builds matches `spec` again. An optional param
+/// left empty is not checked.
+pub fn build_path_with(
+    spec: &RouteSpec,
+    params: &[(&str, &str)],
+    matchers: &ParamMatchers,
+) -> Result<String, NavigateError> {
+    for param in &spec.params {
+        let Some(matcher) = &param.matcher else {
+            continue;
+        };
+        let Some(value) = param_value(params, &param.name) else {
+            continue;
+        };
+
+        let valid = match param.kind {
+            ParamKind::CatchAll | ParamKind::OptionalCatchAll => value
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .all(|segment| matchers.matches(matcher, segment)),
+            ParamKind::Optional if value.is_empty() => true,
+            ParamKind::Single | ParamKind::Optional => matchers.matches(matcher, value),
+        };
+
+        if !valid {
+            return Err(NavigateError::InvalidParam {
+                name: param.name.clone(),
+                matcher: matcher.clone(),
+            });
+        }
+    }
+
+    build_path(spec, params)
+}
+
+/// Builds a path with `build_path` and appends `query` to it.
+pub fn build_url(
+    spec: &RouteSpec,
+    params: &[(&str, &str)],
+    query: &[(&str, &str)],
+) -> Result<String, NavigateError> {
+    let location = query.iter().fold(
+        Location::new(build_path(spec, params)?),
+        |location, (name, value)| location.with_query(*name, *value),
+    );
+
+    Ok(location.to_string())
+}
+
+/// Matches `path` against a single route, checking constrained params with
+/// the built-in matchers. Captured values are percent-decoded, and a path
+/// that fails to decode does not match.
+pub fn match_route(spec: &RouteSpec, path: &str) -> Option<RouteMatch> {
+    match_route_with(spec, path, ParamMatchers::builtin())
+}
+
+/// Like `match_route`, but reports a malformed escape or invalid utf-8 in the
+/// path instead of treating it as a miss.
+pub fn try_match_route(spec: &RouteSpec, path: &str) -> Result<Option<RouteMatch>, DecodeError> {
+    try_match_route_with(spec, path, ParamMatchers::builtin())
+}
+
+pub fn match_route_with(
+    spec: &RouteSpec,
+    path: &str,
+    matchers: &ParamMatchers,
+) -> Option<RouteMatch> {
+    try_match_route_with(spec, path, matchers).ok().flatten()
+}
+
+pub fn try_match_route_with(
+    spec: &RouteSpec,
+    path: &str,
+    matchers: &ParamMatchers,
+) -> Result<Option<RouteMatch>, DecodeError> {
+    let location = Location::parse(path);
+    let path_segments = decode_segments(&location.path)?;
+    let path_segments: Vec<&str> = path_segments.iter().map(String::as_str).collect();
+
+    Ok(match_segments(spec, &path_segments, matchers)
+        .map(|params| RouteMatch::at(spec, params, location)))
+}
+
+/// The params `spec` captures from already decoded `path_segments`.
+fn match_segments(
+    spec: &RouteSpec,
+    path_segments: &[&str],
+    matchers: &ParamMatchers,
+) -> Option<Vec<(String, String)>> {
+    let tokens = tokenize_pattern(&spec.path, &spec.params);
+    let mut params: Vec<(String, String)> = Vec::new();
+
+    match_tokens(&tokens, path_segments, 0, 0, matchers, &mut params).then_some(params)
+}
+
+fn match_tokens(
+    tokens: &[Token],
+    path_segments: &[&str],
+    token_index: usize,
+    path_index: usize,
+    matchers: &ParamMatchers,
+    params: &mut Vec<(String, String)>,
+) -> bool {
+    if token_index == tokens.len() {
+        return path_index == path_segments.len();
+    }
+
+    let token = &tokens[token_index];
+    for (next, captured) in advance(token, path_segments, path_index, matchers) {
+        let len = params.len();
+        params.extend(captured);
+
+        if match_tokens(tokens, path_segments, token_index + 1, next, matchers, params) {
+            return true;
+        }
+
+        params.truncate(len);
+    }
+
+    false
+}
@@ -4,18 +4,26 @@ pub enum RouteKind {
     Dynamic,
     CatchAll,
     Fallback,
+    /// A `_layout` file wrapping every route in its directory.
+    Layout,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParamKind {
     Single,
     CatchAll,
+    /// A catch-all that also matches zero segments.
+    OptionalCatchAll,
+    /// A single segment that may be left out.
+    Optional,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParamSpec {
     pub name: String,
     pub kind: ParamKind,
+    /// The name of the matcher the value must satisfy, from `[id=integer]`.
+    pub matcher: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -25,4 +33,7 @@ pub struct RouteSpec {
     pub kind: RouteKind,
     pub params: Vec<ParamSpec>,
     pub source: String,
+    /// Ids of the `_layout` files that would wrap this route, outermost
+    /// first. Only the ones present in the table apply.
+    pub layouts: Vec<u64>,
 }
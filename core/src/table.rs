@@ -0,0 +1,229 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+use super::conflict::{ConflictKind, RouteConflict, find_conflicts, shape_of};
+use super::errors::RouteError;
+use super::matcher::RouteMatcher;
+use super::param_matcher::ParamMatchers;
+use super::spec::{RouteKind, RouteSpec};
+
+/// Routes are only added and removed through the table's methods, since
+/// the compiled matcher refers to them by index.
+#[derive(Debug, Clone)]
+pub struct RouteTable<T> {
+    routes: Vec<(RouteSpec, T)>,
+    fallbacks: Vec<(RouteSpec, T)>,
+    layouts: Vec<(RouteSpec, T)>,
+    matchers: ParamMatchers,
+    matcher: OnceLock<RouteMatcher>,
+}
+
+impl<T> Default for RouteTable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> RouteTable<T> {
+    pub fn new() -> Self {
+        Self {
+            routes: Vec::new(),
+            fallbacks: Vec::new(),
+            layouts: Vec::new(),
+            matchers: ParamMatchers::new(),
+            matcher: OnceLock::new(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            routes: Vec::with_capacity(capacity),
+            fallbacks: Vec::new(),
+            layouts: Vec::new(),
+            matchers: ParamMatchers::new(),
+            matcher: OnceLock::new(),
+        }
+    }
+
+    pub fn insert(&mut self, spec: RouteSpec, handler: T) -> Result<(), RouteError> {
+        match spec.kind {
+            RouteKind::Fallback => return self.insert_fallback(spec, handler),
+            RouteKind::Layout => return self.insert_layout(spec, handler),
+            _ => {}
+        }
+
+        if self.routes.iter().any(|(existing, _)| existing.path == spec.path) {
+            return Err(RouteError::DuplicateRoute(spec.path));
+        }
+
+        if self.contains_id(spec.id) {
+            return Err(RouteError::DuplicateId(spec.id));
+        }
+
+        let shape = shape_of(&spec);
+        if let Some((existing, _)) = self
+            .routes
+            .iter()
+            .find(|(existing, _)| shape_of(existing) == shape)
+        {
+            let conflict = RouteConflict::new(ConflictKind::Shadowed, existing, &spec);
+            return Err(RouteError::ShadowedRoute(Box::new(conflict)));
+        }
+
+        self.routes.push((spec, handler));
+        self.invalidate();
+        Ok(())
+    }
+
+    fn insert_fallback(&mut self, spec: RouteSpec, handler: T) -> Result<(), RouteError> {
+        let shape = shape_of(&spec);
+        if self.fallbacks.iter().any(|(existing, _)| shape_of(existing) == shape) {
+            return Err(RouteError::MultipleFallbacks);
+        }
+
+        if self.contains_id(spec.id) {
+            return Err(RouteError::DuplicateId(spec.id));
+        }
+
+        self.fallbacks.push((spec, handler));
+        Ok(())
+    }
+
+    fn insert_layout(&mut self, spec: RouteSpec, handler: T) -> Result<(), RouteError> {
+        if self.contains_id(spec.id) {
+            return Err(RouteError::DuplicateId(spec.id));
+        }
+
+        self.layouts.push((spec, handler));
+        Ok(())
+    }
+
+    /// Ids are unique across routes, fallbacks and layouts, since
+    /// `handler_mut` and `remove` look them up in all three.
+    fn contains_id(&self, id: u64) -> bool {
+        self.routes
+            .iter()
+            .chain(&self.fallbacks)
+            .chain(&self.layouts)
+            .any(|(spec, _)| spec.id == id)
+    }
+
+    pub fn routes(&self) -> &[(RouteSpec, T)] {
+        &self.routes
+    }
+
+    /// `404` pages, each covering the directory it was declared in.
+    pub fn fallbacks(&self) -> &[(RouteSpec, T)] {
+        &self.fallbacks
+    }
+
+    /// `_layout` pages, applied to the routes listing them in
+    /// `RouteSpec::layouts`.
+    pub fn layouts(&self) -> &[(RouteSpec, T)] {
+        &self.layouts
+    }
+
+    /// The handler of the route, fallback or layout with `id`.
+    pub fn handler_mut(&mut self, id: u64) -> Option<&mut T> {
+        self.routes
+            .iter_mut()
+            .chain(&mut self.fallbacks)
+            .chain(&mut self.layouts)
+            .find(|(spec, _)| spec.id == id)
+            .map(|(_, handler)| handler)
+    }
+
+    /// Removes the route, fallback or layout with `id`.
+    pub fn remove(&mut self, id: u64) -> Option<(RouteSpec, T)> {
+        for list in [&mut self.routes, &mut self.fallbacks, &mut self.layouts] {
+            if let Some(index) = list.iter().position(|(spec, _)| spec.id == id) {
+                let removed = list.remove(index);
+                self.matcher.take();
+                return Some(removed);
+            }
+        }
+
+        None
+    }
+
+    /// The layouts wrapping `spec`, outermost first.
+    pub fn layouts_for(&self, spec: &RouteSpec) -> Vec<&(RouteSpec, T)> {
+        spec.layouts
+            .iter()
+            .filter_map(|id| self.layouts.iter().find(|(layout, _)| layout.id == *id))
+            .collect()
+    }
+
+    /// Pairs of routes that both match some path with the same score, so the
+    /// one inserted first always wins. Shadowed routes are already rejected
+    /// by `insert` and `from_routes`.
+    pub fn conflicts(&self) -> Vec<RouteConflict> {
+        find_conflicts(self.routes.iter().map(|(spec, _)| spec))
+    }
+
+    /// Drops the compiled matcher so it is rebuilt on the next lookup.
+    pub fn invalidate(&mut self) {
+        self.matcher.take();
+    }
+
+    /// Registers a param matcher for routes such as `[id=name]`, alongside
+    /// the built-in `integer`, `uuid` and `slug`.
+    pub fn register_matcher<F>(&mut self, name: impl Into<String>, matcher: F)
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.matchers.register(name, matcher);
+        self.invalidate();
+    }
+
+    pub fn matchers(&self) -> &ParamMatchers {
+        &self.matchers
+    }
+
+    pub fn matcher(&self) -> &RouteMatcher {
+        self.matcher.get_or_init(|| RouteMatcher::new(self))
+    }
+
+    pub fn from_routes<I>(routes: I) -> Result<Self, RouteError>
+    where
+        I: IntoIterator<Item = (RouteSpec, T)>,
+    {
+        let mut table = Self::new();
+        let mut seen_paths: HashSet<String> = HashSet::new();
+        let mut seen_shapes = HashMap::new();
+
+        for (spec, handler) in routes {
+            match spec.kind {
+                RouteKind::Fallback => {
+                    table.insert_fallback(spec, handler)?;
+                    continue;
+                }
+                RouteKind::Layout => {
+                    table.insert_layout(spec, handler)?;
+                    continue;
+                }
+                _ => {}
+            }
+
+            if !seen_paths.insert(spec.path.clone()) {
+                return Err(RouteError::DuplicateRoute(spec.path));
+            }
+
+            if table.contains_id(spec.id) {
+                return Err(RouteError::DuplicateId(spec.id));
+            }
+
+            let shape = shape_of(&spec);
+            if let Some(&index) = seen_shapes.get(&shape) {
+                let (existing, _) = &table.routes[index];
+                let conflict = RouteConflict::new(ConflictKind::Shadowed, existing, &spec);
+                return Err(RouteError::ShadowedRoute(Box::new(conflict)));
+            }
+            seen_shapes.insert(shape, table.routes.len());
+
+            table.routes.push((spec, handler));
+        }
+
+        Ok(table)
+    }
+}
@@ -0,0 +1,135 @@
+//! The `routes!` macro re-exported by `fs-router`.
+//!
+//! The route grammar and code generation come from `fs-router-core`, which
+//! the main crate re-exports as `fs_router::core`.
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+use fs_router_core::build;
+
+/// Scans a page directory at compile time and expands to its `RouteTable`.
+///
+/// ```ignore
+/// let table = fs_router::routes!("pages");
+/// ```
+///
+/// The directory is resolved against the crate being compiled. Each page is
+/// declared as a module and its `page()` constructor provides the handler.
+/// Grammar errors, duplicate paths and multiple fallbacks are reported as
+/// compile errors naming the offending files.
+///
+/// The expansion is only redone when a page file it declares changes, so
+/// adding or removing a page needs a rebuild of the crate, e.g. after
+/// `cargo clean -p <crate>` or touching the file that calls the macro. A
+/// build script using `fs_router::build::generate` does not have this
+/// limitation.
+#[proc_macro]
+pub fn routes(input: TokenStream) -> TokenStream {
+    let (page_dir, span) = match parse_page_dir(input) {
+        Ok(parsed) => parsed,
+        Err((message, span)) => return compile_error(&message, span),
+    };
+
+    let expanded = match expand(&page_dir) {
+        Ok(expanded) => expanded,
+        Err(message) => return compile_error(&message, span),
+    };
+
+    match expanded.parse() {
+        Ok(tokens) => tokens,
+        Err(err) => compile_error(&format!("fs_router::routes! : {err}"), span),
+    }
+}
+
+/// The source `routes!` expands to, or the message of its compile error.
+fn expand(page_dir: &str) -> Result<String, String> {
+    let pages = build::Generator::new(page_dir)
+        .pages()
+        .map_err(|err| format!("fs_router::routes! : {err}"))?;
+
+    Ok(format!(
+        "{{{}\n{}\n}}",
+        build::render_modules(&pages),
+        build::render_table_expr(&pages, "page")
+    ))
+}
+
+fn parse_page_dir(input: TokenStream) -> Result<(String, Span), (String, Span)> {
+    let mut tokens = input.into_iter();
+
+    let literal = match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(literal)), None) => literal,
+        (Some(token), _) => {
+            return Err((
+                "expected a page directory string literal".to_string(),
+                token.span(),
+            ));
+        }
+        (None, _) => {
+            return Err((
+                "expected a page directory string literal".to_string(),
+                Span::call_site(),
+            ));
+        }
+    };
+
+    let span = literal.span();
+    let text = literal.to_string();
+
+    let value = text
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .filter(|s| !s.contains('\\'))
+        .ok_or_else(|| {
+            (
+                "expected a plain string literal without escapes".to_string(),
+                span,
+            )
+        })?;
+
+    Ok((value.to_string(), span))
+}
+
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let mut literal = Literal::string(message);
+    literal.set_span(span);
+
+    let mut group = Group::new(Delimiter::Parenthesis, TokenTree::Literal(literal).into());
+    group.set_span(span);
+
+    let mut bang = Punct::new('!', Spacing::Alone);
+    bang.set_span(span);
+
+    [
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(bang),
+        TokenTree::Group(group),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand;
+
+    #[test]
+    fn bad_page_trees_are_compile_errors() {
+        let err = expand("tests/fixtures/duplicate").unwrap_err();
+        assert!(err.starts_with("fs_router::routes! : "), "{err}");
+        assert!(err.contains("about.rs"), "{err}");
+
+        let err = expand("tests/fixtures/invalid").unwrap_err();
+        assert!(err.contains("[id.rs"), "{err}");
+
+        let err = expand("tests/fixtures/missing").unwrap_err();
+        assert!(err.starts_with("fs_router::routes! : "), "{err}");
+    }
+
+    #[test]
+    fn expands_every_page() {
+        let expanded = expand("tests/fixtures/valid").unwrap();
+        assert!(expanded.contains("pub mod page_about;"), "{expanded}");
+        assert!(expanded.contains("::fs_router::RouteTable::from_routes"), "{expanded}");
+    }
+}
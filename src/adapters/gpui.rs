@@ -1,324 +1,921 @@
-use crate::core::{ParamKind, RouteSpec, RouteTable};
-use gpui::{AnyView, Context, IntoElement, Render, SharedString, Window, div, prelude::*, px, rgb};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::panic;
+use std::rc::Rc;
+
+use crate::core::{Location, RouteTable};
+use crate::core::events::RouteChanged;
+use crate::core::guard::{GuardDecision, LeaveDecision, NavigationGuards};
+use crate::core::history::History;
+use crate::core::navigator::{Navigator, Transition};
+use gpui::{
+    AnyElement, AnyView, App, Bounds, Context, Element, ElementId, Entity, EntityId, EventEmitter,
+    FocusHandle, Global, GlobalElementId, InspectorElementId, IntoElement, KeyBinding, LayoutId,
+    MouseButton, NavigationDirection, Pixels, Render, SharedString, WeakEntity, Window, actions,
+    div, prelude::*, px, rgb,
+};
+
+pub use crate::core::errors::NavigateError;
+pub use crate::core::router::{RouteMatch, build_path, match_route};
+use crate::core::router::match_route_with;
+
+actions!(fs_router, [Back, Forward]);
+
+/// Builds a page's view from the route it was matched with. Called once per
+/// navigation, not on every render.
+pub type RouteHandler = Rc<dyn Fn(&RouteMatch, &mut Window, &mut App) -> AnyView>;
+
+pub fn page<F>(factory: F) -> RouteHandler
+where
+    F: Fn(&RouteMatch, &mut Window, &mut App) -> AnyView + 'static,
+{
+    Rc::new(factory)
+}
 
-#[derive(Debug, Clone)]
-pub struct RouteMatch {
-    pub spec: RouteSpec,
-    pub params: Vec<(String, String)>,
+/// A handler that ignores the params and always shows `view`.
+pub fn view(view: impl Into<AnyView>) -> RouteHandler {
+    let view = view.into();
+    Rc::new(move |_, _, _| view.clone())
 }
 
-pub struct RouterView {
-    table: RouteTable<AnyView>,
-    current_route: SharedString,
+/// The slot a layout renders its page, or its nested layout, into. The
+/// router swaps the child on navigation while the layout itself stays put.
+#[derive(Default)]
+pub struct Outlet {
+    child: Option<AnyView>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum NavigateError {
-    RouteIdNotFound(u64),
-    MissingParam { name: String },
-    UnsupportedWildcard,
+impl Outlet {
+    pub fn child(&self) -> Option<&AnyView> {
+        self.child.as_ref()
+    }
 }
 
-impl RouterView {
-    pub fn new(table: RouteTable<AnyView>, initial_route: impl Into<SharedString>) -> Self {
+impl Render for Outlet {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div().size_full().when_some(self.child.clone(), |d, child| d.child(child))
+    }
+}
+
+struct LayoutOutlet(Entity<Outlet>);
+
+impl Global for LayoutOutlet {}
+
+/// Asked before the router leaves the page that registered it, with the
+/// location it is about to show.
+pub type LeaveHook = Rc<dyn Fn(&Location, &mut App) -> LeaveDecision>;
+
+struct LeaveHooks(Vec<LeaveHook>);
+
+impl Global for LeaveHooks {}
+
+/// Registers a hook the router consults before navigating away from the page
+/// or layout being built. Only takes effect while a factory runs; the hook is
+/// dropped along with its view. A layout's hook is only asked when the
+/// navigation leaves the layout, not when it moves between its pages.
+pub fn on_leave<F>(cx: &mut App, hook: F)
+where
+    F: Fn(&Location, &mut App) -> LeaveDecision + 'static,
+{
+    if cx.has_global::<LeaveHooks>() {
+        cx.global_mut::<LeaveHooks>().0.push(Rc::new(hook));
+    }
+}
+
+/// Every router that has rendered, so views can navigate without holding
+/// one. `building` is the stack of routers whose factories, leave hooks or
+/// pages are running, with the route they run for, and `views` maps each
+/// view known to sit inside a router to that router.
+#[derive(Default)]
+struct Routers {
+    building: Vec<(WeakEntity<RouterView>, RouteMatch)>,
+    all: Vec<WeakEntity<RouterView>>,
+    named: HashMap<SharedString, WeakEntity<RouterView>>,
+    views: RefCell<HashMap<EntityId, OwnedView>>,
+}
+
+impl Global for Routers {}
+
+/// A view and the router it sits in, forgotten once either is released.
+struct OwnedView {
+    alive: Box<dyn Fn() -> bool>,
+    router: WeakEntity<RouterView>,
+}
+
+impl Routers {
+    /// The router that owns the view `id`: the innermost one building or
+    /// rendering, which `id` is then remembered to sit in, or the one it was
+    /// remembered in before.
+    fn owner<V: 'static>(&self, id: EntityId, view: &WeakEntity<V>) -> Option<RouterHandle> {
+        if let Some((router, _)) = self.building.last() {
+            let view = view.clone();
+            self.views.borrow_mut().insert(
+                id,
+                OwnedView {
+                    alive: Box::new(move || view.upgrade().is_some()),
+                    router: router.clone(),
+                },
+            );
+            return Some(RouterHandle::new(router.clone()));
+        }
+
+        let views = self.views.borrow();
+        let router = &views.get(&id)?.router;
+        Some(RouterHandle::new(router.clone()))
+    }
+
+    /// Forgets the routers and views that have been released.
+    fn prune(&mut self) {
+        self.all.retain(|router| router.upgrade().is_some());
+        self.named.retain(|_, router| router.upgrade().is_some());
+        self.views
+            .get_mut()
+            .retain(|_, view| (view.alive)() && view.router.upgrade().is_some());
+    }
+
+    /// The router being built, or the only one alive. With several routers
+    /// and none of them building, the handle fails with `AmbiguousRouter`.
+    fn nearest(&self) -> RouterHandle {
+        if let Some((router, _)) = self.building.last() {
+            return RouterHandle::new(router.clone());
+        }
+
+        let mut alive = self.all.iter().filter(|router| router.upgrade().is_some());
+        match (alive.next(), alive.next()) {
+            (Some(router), None) => RouterHandle::new(router.clone()),
+            (Some(_), Some(_)) => RouterHandle {
+                router: None,
+                ambiguous: true,
+            },
+            (None, _) => RouterHandle::default(),
+        }
+    }
+}
+
+/// Access to the route the router is showing and to the router itself, for
+/// use from page views.
+pub trait RouteContext {
+    /// The route shown by the router that owns the caller, see `router`.
+    /// While a factory runs it is the route being built.
+    fn current_route(&self) -> Option<&RouteMatch>;
+
+    /// The outlet of the layout being built. Only set while a layout's
+    /// factory runs; the layout keeps it and renders it as a child.
+    fn outlet(&self) -> Option<Entity<Outlet>>;
+
+    /// The router that owns the caller: the one whose factory is running or
+    /// whose page is rendering. A view's own context also remembers the
+    /// router of a page or layout it built, or that it rendered inside when
+    /// it last asked. Anywhere else it is the only router alive, and with
+    /// several of them navigating fails with `AmbiguousRouter`.
+    fn router(&self) -> RouterHandle;
+
+    /// The router created with `with_name(name)`.
+    fn router_named(&self, name: &str) -> RouterHandle;
+
+    fn route_param(&self, name: &str) -> Option<&str> {
+        self.current_route()?.param(name)
+    }
+}
+
+impl RouteContext for App {
+    fn current_route(&self) -> Option<&RouteMatch> {
+        if let Some((_, matched)) = self.try_global::<Routers>()?.building.last() {
+            return Some(matched);
+        }
+
+        self.router().entity()?.read(self).current_match()
+    }
+
+    fn outlet(&self) -> Option<Entity<Outlet>> {
+        self.try_global::<LayoutOutlet>().map(|outlet| outlet.0.clone())
+    }
+
+    fn router(&self) -> RouterHandle {
+        self.try_global::<Routers>()
+            .map(Routers::nearest)
+            .unwrap_or_default()
+    }
+
+    fn router_named(&self, name: &str) -> RouterHandle {
+        self.try_global::<Routers>()
+            .and_then(|routers| routers.named.get(name))
+            .cloned()
+            .map(RouterHandle::new)
+            .unwrap_or_default()
+    }
+}
+
+impl<V: 'static> RouteContext for Context<'_, V> {
+    fn current_route(&self) -> Option<&RouteMatch> {
+        let routers = self.try_global::<Routers>()?;
+        if let Some((_, matched)) = routers.building.last() {
+            routers.owner(self.entity_id(), &self.weak_entity());
+            return Some(matched);
+        }
+
+        self.router().entity()?.read(self).current_match()
+    }
+
+    fn outlet(&self) -> Option<Entity<Outlet>> {
+        (**self).outlet()
+    }
+
+    fn router(&self) -> RouterHandle {
+        self.try_global::<Routers>()
+            .and_then(|routers| routers.owner(self.entity_id(), &self.weak_entity()))
+            .unwrap_or_else(|| (**self).router())
+    }
+
+    fn router_named(&self, name: &str) -> RouterHandle {
+        (**self).router_named(name)
+    }
+}
+
+/// A weak reference to a `RouterView` that navigates it from anywhere with
+/// an `App`. Navigating through a handle whose router is gone, or that never
+/// had one, fails with `NoRouter`. The router must not be mid-update, so
+/// navigate from event handlers rather than from factories or renders.
+#[derive(Clone, Default)]
+pub struct RouterHandle {
+    router: Option<WeakEntity<RouterView>>,
+    ambiguous: bool,
+}
+
+impl RouterHandle {
+    fn new(router: WeakEntity<RouterView>) -> Self {
         Self {
-            table,
-            current_route: initial_route.into(),
+            router: Some(router),
+            ambiguous: false,
         }
     }
 
-    pub fn route(&self) -> &str {
-        &self.current_route
+    pub fn entity(&self) -> Option<Entity<RouterView>> {
+        self.router.as_ref()?.upgrade()
     }
 
-    pub fn navigate(&mut self, route: impl Into<SharedString>, cx: &mut Context<Self>) {
-        self.current_route = route.into();
-        cx.notify();
+    pub fn route(&self, cx: &App) -> Option<SharedString> {
+        Some(self.entity()?.read(cx).route().to_string().into())
     }
 
-    pub fn set_route(&mut self, route: impl Into<SharedString>, cx: &mut Context<Self>) {
-        self.navigate(route, cx);
+    pub fn navigate(
+        &self,
+        route: impl Into<SharedString>,
+        cx: &mut App,
+    ) -> Result<(), NavigateError> {
+        self.update(cx, |router, cx| router.navigate(route, cx))
+    }
+
+    pub fn replace(
+        &self,
+        route: impl Into<SharedString>,
+        cx: &mut App,
+    ) -> Result<(), NavigateError> {
+        self.update(cx, |router, cx| router.replace(route, cx))
     }
 
     pub fn navigate_by_id(
-        &mut self,
+        &self,
         route_id: u64,
         params: &[(&str, &str)],
-        cx: &mut Context<Self>,
+        cx: &mut App,
     ) -> Result<(), NavigateError> {
-        let (spec, _) = self
-            .table
-            .routes
-            .iter()
-            .find(|(spec, _)| spec.id == route_id)
-            .ok_or(NavigateError::RouteIdNotFound(route_id))?;
+        self.update(cx, |router, cx| router.navigate_by_id(route_id, params, cx))
+    }
 
-        let route = build_path(spec, params)?;
-        self.navigate(route, cx);
-        Ok(())
+    pub fn back(&self, cx: &mut App) -> Result<bool, NavigateError> {
+        self.update(cx, |router, cx| router.back(cx))
     }
 
-    pub fn table(&self) -> &RouteTable<AnyView> {
-        &self.table
+    pub fn forward(&self, cx: &mut App) -> Result<bool, NavigateError> {
+        self.update(cx, |router, cx| router.forward(cx))
     }
 
-    pub fn table_mut(&mut self) -> &mut RouteTable<AnyView> {
-        &mut self.table
+    pub fn go(&self, delta: isize, cx: &mut App) -> Result<bool, NavigateError> {
+        self.update(cx, |router, cx| router.go(delta, cx))
     }
-}
 
-impl Render for RouterView {
-    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
-        let resolved = resolve_any_view(&self.table, &self.current_route);
+    fn update<R>(
+        &self,
+        cx: &mut App,
+        update: impl FnOnce(&mut RouterView, &mut Context<RouterView>) -> Result<R, NavigateError>,
+    ) -> Result<R, NavigateError> {
+        let router = self.entity().ok_or(if self.ambiguous {
+            NavigateError::AmbiguousRouter
+        } else {
+            NavigateError::NoRouter
+        })?;
+        router.update(cx, update)
+    }
+}
 
-        let (matched_view, matched_meta) = match resolved {
-            Some(resolved) => {
-                let view = Some(resolved.view);
-                let meta = Some(resolved.matched);
-                (view, meta)
-            }
-            None => (self.table.fallback.clone(), None),
-        };
+const KEY_CONTEXT: &str = "RouterView";
 
-        let header = render_debug_header(&self.current_route, matched_meta.as_ref(), &self.table);
+/// Binds Alt+Left and Alt+Right to `Back` and `Forward` inside any router.
+pub fn bind_history_keys(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("alt-left", Back, Some(KEY_CONTEXT)),
+        KeyBinding::new("alt-right", Forward, Some(KEY_CONTEXT)),
+    ]);
+}
 
-        div()
-            .flex()
-            .flex_col()
-            .size_full()
-            .child(header)
-            .child(
-                div()
-                    .flex_1()
-                    .size_full()
-                    .bg(rgb(0xffffff))
-                    .when_some(matched_view, |d, view| d.child(view)),
-            )
-    }
+/// A `Navigator` with a view: it renders the current route's page inside its
+/// layouts and asks their leave hooks before the navigator moves on.
+pub struct RouterView {
+    navigator: Navigator<RouteHandler>,
+    /// What the current route resolves to, kept in step with navigation.
+    current_match: Option<RouteMatch>,
+    pending: Option<PendingNavigation>,
+    name: Option<SharedString>,
+    registered: bool,
+    focus_handle: Option<FocusHandle>,
+    rendered: Option<Rendered>,
+    layouts: Vec<MountedLayout>,
 }
 
-struct ResolvedAnyView {
+/// The view built for `route`, wrapped in its layouts, kept until the router
+/// navigates elsewhere.
+struct Rendered {
+    route: String,
     matched: RouteMatch,
     view: AnyView,
+    leave_hooks: Vec<LeaveHook>,
 }
 
-fn resolve_any_view(table: &RouteTable<AnyView>, path: &str) -> Option<ResolvedAnyView> {
-    let mut best: Option<(usize, RouteMatch, AnyView)> = None;
+/// A navigation held back by a leave hook that deferred.
+struct PendingNavigation {
+    transition: Transition,
+    route: String,
+}
 
-    for (spec, view) in &table.routes {
-        let Some(matched) = match_route(spec, path) else {
-            continue;
-        };
+/// A layout that stays mounted while navigation stays inside it with the same
+/// params.
+struct MountedLayout {
+    id: u64,
+    params: Vec<(String, String)>,
+    view: AnyView,
+    outlet: Entity<Outlet>,
+    leave_hooks: Vec<LeaveHook>,
+}
 
-        let score = score_spec(spec);
-        match &best {
-            Some((best_score, _, _)) if *best_score >= score => {}
-            _ => best = Some((score, matched, view.clone())),
+impl RouterView {
+    pub fn new(table: RouteTable<RouteHandler>, initial_route: impl Into<SharedString>) -> Self {
+        let navigator = Navigator::new(table, initial_route.into().to_string());
+        Self {
+            current_match: navigator.current().map(|(matched, _)| matched),
+            navigator,
+            pending: None,
+            name: None,
+            registered: false,
+            focus_handle: None,
+            rendered: None,
+            layouts: Vec::new(),
         }
     }
 
-    best.map(|(_, matched, view)| ResolvedAnyView { matched, view })
-}
+    /// Keeps at most `max_depth` history entries.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.navigator = self.navigator.with_max_depth(max_depth);
+        self
+    }
 
-fn score_spec(spec: &RouteSpec) -> usize {
-    let tokens = tokenize_pattern(&spec.path, &spec.params);
-    tokens
-        .iter()
-        .map(|t| match t {
-            Token::Static(_) => 100,
-            Token::ParamSingle(_) => 10,
-            Token::ParamCatchAll(_) => 1,
-            Token::Wildcard => 0,
-        })
-        .sum::<usize>()
-        + tokens.len()
-}
+    /// Registers the router under `name`, for `cx.router_named(name)`.
+    pub fn with_name(mut self, name: impl Into<SharedString>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
 
-pub fn build_path(spec: &RouteSpec, params: &[(&str, &str)]) -> Result<String, NavigateError> {
-    let tokens = tokenize_pattern(&spec.path, &spec.params);
-
-    let mut result = String::new();
-    for token in tokens {
-        match token {
-            Token::Static(s) => {
-                if s != "/" {
-                    result.push('/');
-                    result.push_str(&s);
-                }
-            }
-            Token::ParamSingle(name) => {
-                let value = params
-                    .iter()
-                    .find(|(k, _)| *k == name)
-                    .map(|(_, v)| *v)
-                    .ok_or_else(|| NavigateError::MissingParam { name })?;
+    pub fn name(&self) -> Option<&SharedString> {
+        self.name.as_ref()
+    }
 
-                result.push('/');
-                result.push_str(value);
-            }
-            Token::ParamCatchAll(name) => {
-                let value = params
-                    .iter()
-                    .find(|(k, _)| *k == name)
-                    .map(|(_, v)| *v)
-                    .ok_or_else(|| NavigateError::MissingParam { name })?;
-
-                let value = value.trim_matches('/');
-                if !value.is_empty() {
-                    result.push('/');
-                    result.push_str(value);
-                }
+    /// Gives up with `TooManyRedirects` after `max_redirects` guard redirects
+    /// in a single navigation.
+    pub fn with_max_redirects(mut self, max_redirects: usize) -> Self {
+        self.navigator = self.navigator.with_max_redirects(max_redirects);
+        self
+    }
+
+    /// Registers a guard run before every navigation, including history
+    /// moves. It can let the navigation through, redirect it or cancel it.
+    pub fn before_navigate<F>(&mut self, guard: F)
+    where
+        F: Fn(&Location, &Location) -> GuardDecision + 'static,
+    {
+        self.navigator.before_navigate(guard);
+    }
+
+    /// Registers a guard run only for navigations to `prefix` and below.
+    pub fn before_navigate_at<F>(&mut self, prefix: impl Into<String>, guard: F)
+    where
+        F: Fn(&Location, &Location) -> GuardDecision + 'static,
+    {
+        self.navigator.before_navigate_at(prefix, guard);
+    }
+
+    pub fn guards(&self) -> &NavigationGuards {
+        self.navigator.guards()
+    }
+
+    /// The navigator behind the router, with its table, history and guards.
+    pub fn navigator(&self) -> &Navigator<RouteHandler> {
+        &self.navigator
+    }
+
+    pub fn route(&self) -> &str {
+        self.navigator.route()
+    }
+
+    /// The route or fallback `route()` resolves to.
+    pub fn current_match(&self) -> Option<&RouteMatch> {
+        self.current_match.as_ref()
+    }
+
+    pub fn history(&self) -> &History {
+        self.navigator.history()
+    }
+
+    /// Pushes `route`, or the url a guard redirected it to, onto the
+    /// history, dropping any forward entries.
+    pub fn navigate(
+        &mut self,
+        route: impl Into<SharedString>,
+        cx: &mut Context<Self>,
+    ) -> Result<(), NavigateError> {
+        self.begin(Transition::Push, route.into().to_string(), cx)
+    }
+
+    /// Swaps the current history entry for `route`.
+    pub fn replace(
+        &mut self,
+        route: impl Into<SharedString>,
+        cx: &mut Context<Self>,
+    ) -> Result<(), NavigateError> {
+        self.begin(Transition::Replace, route.into().to_string(), cx)
+    }
+
+    pub fn set_route(
+        &mut self,
+        route: impl Into<SharedString>,
+        cx: &mut Context<Self>,
+    ) -> Result<(), NavigateError> {
+        self.navigate(route, cx)
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        self.history().can_go_back()
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        self.history().can_go_forward()
+    }
+
+    pub fn back(&mut self, cx: &mut Context<Self>) -> Result<bool, NavigateError> {
+        self.go(-1, cx)
+    }
+
+    pub fn forward(&mut self, cx: &mut Context<Self>) -> Result<bool, NavigateError> {
+        self.go(1, cx)
+    }
+
+    /// Moves `delta` entries through the history. Returns false, leaving the
+    /// route unchanged, when there is no such entry. A guard redirect
+    /// replaces the entry moved to; a cancel leaves the history where it was.
+    pub fn go(&mut self, delta: isize, cx: &mut Context<Self>) -> Result<bool, NavigateError> {
+        let Some(index) = self.history().peek(delta) else {
+            return Ok(false);
+        };
+
+        let route = self.history().entries()[index].clone();
+        self.begin(Transition::Go(delta), route, cx)?;
+        Ok(true)
+    }
+
+    /// The route a leave hook is holding the router back from, if any.
+    pub fn pending_navigation(&self) -> Option<&str> {
+        self.pending.as_ref().map(|pending| pending.route.as_str())
+    }
+
+    /// Carries out the navigation a leave hook deferred, without asking the
+    /// page again. Returns false when nothing is pending.
+    pub fn confirm_leave(&mut self, cx: &mut Context<Self>) -> Result<bool, NavigateError> {
+        let Some(pending) = self.pending.take() else {
+            return Ok(false);
+        };
+
+        self.commit(pending.transition, pending.route, cx)?;
+        Ok(true)
+    }
+
+    /// Drops the navigation a leave hook deferred, staying on the page.
+    pub fn cancel_leave(&mut self) -> bool {
+        self.pending.take().is_some()
+    }
+
+    /// Asks the current page whether it can be left for `route`, then
+    /// commits, refuses or parks the navigation.
+    fn begin(
+        &mut self,
+        transition: Transition,
+        route: String,
+        cx: &mut Context<Self>,
+    ) -> Result<(), NavigateError> {
+        self.pending = None;
+
+        match self.leave_decision(&route, cx) {
+            LeaveDecision::Leave => self.commit(transition, route, cx),
+            LeaveDecision::Stay => Err(NavigateError::Cancelled),
+            LeaveDecision::Defer => {
+                self.pending = Some(PendingNavigation { transition, route });
+                Err(NavigateError::Deferred)
             }
-            Token::Wildcard => return Err(NavigateError::UnsupportedWildcard),
         }
     }
 
-    if result.is_empty() {
-        result.push('/');
+    /// The first answer other than `Leave` from the leave hooks of the shown
+    /// page and of the layouts `route` would unmount, innermost first.
+    /// Staying on the same route never asks.
+    fn leave_decision(&self, route: &str, cx: &mut Context<Self>) -> LeaveDecision {
+        let Some(rendered) = self
+            .rendered
+            .as_ref()
+            .filter(|rendered| rendered.route == self.route())
+        else {
+            return LeaveDecision::Leave;
+        };
+
+        if self.route() == route {
+            return LeaveDecision::Leave;
+        }
+
+        let kept = self
+            .table()
+            .resolve_or_fallback(route)
+            .map_or(0, |(matched, _)| self.kept_layouts(&self.layout_chain(&matched, route)));
+
+        let mut hooks = rendered.leave_hooks.clone();
+        for layout in self.layouts[kept..].iter().rev() {
+            hooks.extend(layout.leave_hooks.iter().cloned());
+        }
+
+        let this = cx.entity().downgrade();
+        cx.default_global::<Routers>()
+            .building
+            .push((this, rendered.matched.clone()));
+
+        let to = Location::parse(route);
+        let decision = hooks
+            .iter()
+            .map(|hook| hook(&to, cx))
+            .find(|decision| *decision != LeaveDecision::Leave)
+            .unwrap_or(LeaveDecision::Leave);
+
+        cx.default_global::<Routers>().building.pop();
+        decision
     }
 
-    Ok(result)
-}
+    /// Hands the navigation to the navigator, which runs the guards and
+    /// moves the history, then re-emits its `RouteChanged`.
+    fn commit(
+        &mut self,
+        transition: Transition,
+        route: String,
+        cx: &mut Context<Self>,
+    ) -> Result<(), NavigateError> {
+        let changed = self.navigator.commit(transition, &route)?;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum Token {
-    Static(String),
-    ParamSingle(String),
-    ParamCatchAll(String),
-    Wildcard,
-}
+        self.current_match = changed.as_ref().map(|changed| changed.to.clone());
+        if let Some(changed) = changed {
+            cx.emit(changed);
+        }
 
-fn tokenize_pattern(pattern: &str, params: &[crate::core::ParamSpec]) -> Vec<Token> {
-    let catchall_params: std::collections::HashSet<&str> = params
-        .iter()
-        .filter(|p| p.kind == ParamKind::CatchAll)
-        .map(|p| p.name.as_str())
-        .collect();
+        cx.notify();
+        Ok(())
+    }
 
-    let mut segments: Vec<&str> = pattern
-        .split('/')
-        .filter(|s| !s.is_empty())
-        .collect();
+    pub fn navigate_by_id(
+        &mut self,
+        route_id: u64,
+        params: &[(&str, &str)],
+        cx: &mut Context<Self>,
+    ) -> Result<(), NavigateError> {
+        let route = self.table().path_for(route_id, params)?;
+        self.navigate(route, cx)
+    }
 
-    if segments.is_empty() {
-        segments.push("/");
+    pub fn table(&self) -> &RouteTable<RouteHandler> {
+        self.navigator.table()
     }
 
-    let mut tokens = Vec::new();
-    let mut i = 0;
-    while i < segments.len() {
-        let seg = segments[i];
+    /// The current page and its layouts are rebuilt on the next render, since
+    /// their handlers may be changed through the returned reference.
+    pub fn table_mut(&mut self) -> &mut RouteTable<RouteHandler> {
+        self.rendered = None;
+        self.layouts.clear();
+        self.navigator.table_mut()
+    }
 
-        if seg == "*" {
-            tokens.push(Token::Wildcard);
-            i += 1;
-            continue;
+    /// Makes the router reachable through `cx.router()`, and by name when it
+    /// has one, once it first renders.
+    fn register(&mut self, cx: &mut Context<Self>) {
+        if self.registered {
+            return;
         }
 
-        if let Some(name) = seg.strip_prefix(':') {
-            let is_catchall = catchall_params.contains(name);
+        let this = cx.entity().downgrade();
+        let routers = cx.default_global::<Routers>();
+        routers.prune();
+        routers.all.push(this.clone());
+        if let Some(name) = &self.name {
+            routers.named.insert(name.clone(), this);
+        }
 
-            if is_catchall && segments.get(i + 1).copied() == Some("*") {
-                tokens.push(Token::ParamCatchAll(name.to_string()));
-                i += 2;
-                continue;
-            }
+        self.registered = true;
+    }
 
-            tokens.push(Token::ParamSingle(name.to_string()));
-            i += 1;
-            continue;
+    /// Builds the view for the current route unless it is already built.
+    fn render_page(
+        &mut self,
+        this: &WeakEntity<Self>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<&Rendered> {
+        if self
+            .rendered
+            .as_ref()
+            .is_some_and(|rendered| rendered.route == self.navigator.route())
+        {
+            return self.rendered.as_ref();
+        }
+
+        let Some((matched, handler)) = self.navigator.current() else {
+            self.current_match = None;
+            self.rendered = None;
+            self.layouts.clear();
+            return None;
+        };
+
+        // The table may have changed since the last navigation.
+        self.current_match = Some(matched.clone());
+
+        let handler = handler.clone();
+        cx.default_global::<Routers>()
+            .building
+            .push((this.clone(), matched.clone()));
+        cx.set_global(LeaveHooks(Vec::new()));
+        let page = handler(&matched, window, cx);
+        let leave_hooks = cx.remove_global::<LeaveHooks>().0;
+        let mut built = vec![page.clone()];
+        let view = self.mount_layouts(&matched, page, window, cx);
+        built.extend(self.layouts.iter().map(|layout| layout.view.clone()));
+
+        let routers = cx.default_global::<Routers>();
+        routers.building.pop();
+        for view in built {
+            let weak = view.downgrade();
+            routers.views.get_mut().insert(
+                view.entity_id(),
+                OwnedView {
+                    alive: Box::new(move || weak.upgrade().is_some()),
+                    router: this.clone(),
+                },
+            );
+        }
+
+        self.rendered = Some(Rendered {
+            route: self.navigator.route().to_string(),
+            matched,
+            view,
+            leave_hooks,
+        });
+        self.rendered.as_ref()
+    }
+
+    /// The layouts wrapping `matched`, outermost first, each matched against
+    /// `path` for its params.
+    fn layout_chain(&self, matched: &RouteMatch, path: &str) -> Vec<(RouteMatch, RouteHandler)> {
+        self.table()
+            .layouts_for(&matched.spec)
+            .into_iter()
+            .map(|(spec, handler)| {
+                let layout = match_route_with(spec, path, self.table().matchers()).unwrap_or_else(
+                    || RouteMatch {
+                        spec: spec.clone(),
+                        params: Vec::new(),
+                        location: matched.location.clone(),
+                    },
+                );
+                (layout, handler.clone())
+            })
+            .collect()
+    }
+
+    /// How many of the mounted layouts `chain` keeps.
+    fn kept_layouts(&self, chain: &[(RouteMatch, RouteHandler)]) -> usize {
+        self.layouts
+            .iter()
+            .zip(chain)
+            .take_while(|(mounted, (layout, _))| {
+                mounted.id == layout.spec.id && mounted.params == layout.params
+            })
+            .count()
+    }
+
+    /// Wraps `page` in its layouts and returns the outermost view. Layouts
+    /// already mounted with the same params are kept, so only the part of the
+    /// tree below them is rebuilt.
+    fn mount_layouts(
+        &mut self,
+        matched: &RouteMatch,
+        page: AnyView,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> AnyView {
+        let chain = self.layout_chain(matched, self.route());
+        let kept = self.kept_layouts(&chain);
+        self.layouts.truncate(kept);
+
+        for (layout, handler) in chain.into_iter().skip(kept) {
+            let outlet = cx.new(|_| Outlet::default());
+            cx.set_global(LayoutOutlet(outlet.clone()));
+            cx.set_global(LeaveHooks(Vec::new()));
+            let view = handler(&layout, window, cx);
+            let leave_hooks = cx.remove_global::<LeaveHooks>().0;
+            cx.remove_global::<LayoutOutlet>();
+
+            self.layouts.push(MountedLayout {
+                id: layout.spec.id,
+                params: layout.params,
+                view,
+                outlet,
+                leave_hooks,
+            });
+        }
+
+        // Outlets of the layouts above the last kept one still hold the
+        // right child.
+        let mut child = page;
+        for mounted in self.layouts.iter().skip(kept.saturating_sub(1)).rev() {
+            mounted.outlet.update(cx, |outlet, cx| {
+                outlet.child = Some(child);
+                cx.notify();
+            });
+            child = mounted.view.clone();
         }
 
-        tokens.push(Token::Static(seg.to_string()));
-        i += 1;
+        self.layouts
+            .first()
+            .map(|layout| layout.view.clone())
+            .unwrap_or(child)
     }
+}
+
+impl EventEmitter<RouteChanged> for RouterView {}
+
+/// Lays out and paints a router's page with the router pushed on
+/// `Routers::building`, so views rendered anywhere inside the page resolve
+/// `cx.router()` and `cx.current_route()` to it.
+struct RouteScope {
+    router: WeakEntity<RouterView>,
+    matched: RouteMatch,
+    child: AnyElement,
+}
 
-    tokens
+impl RouteScope {
+    fn scoped<R>(&mut self, cx: &mut App, f: impl FnOnce(&mut AnyElement, &mut App) -> R) -> R {
+        cx.default_global::<Routers>()
+            .building
+            .push((self.router.clone(), self.matched.clone()));
+        let result = f(&mut self.child, cx);
+        cx.default_global::<Routers>().building.pop();
+        result
+    }
 }
 
-pub fn match_route(spec: &RouteSpec, path: &str) -> Option<RouteMatch> {
-    let tokens = tokenize_pattern(&spec.path, &spec.params);
+impl IntoElement for RouteScope {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
 
-    let path_segments: Vec<&str> = path
-        .split('/')
-        .filter(|s| !s.is_empty())
-        .collect();
+impl Element for RouteScope {
+    type RequestLayoutState = ();
+    type PrepaintState = ();
 
-    let mut params: Vec<(String, String)> = Vec::new();
+    fn id(&self) -> Option<ElementId> {
+        None
+    }
 
-    if match_tokens(&tokens, &path_segments, 0, 0, &mut params) {
-        Some(RouteMatch {
-            spec: spec.clone(),
-            params,
-        })
-    } else {
+    fn source_location(&self) -> Option<&'static panic::Location<'static>> {
         None
     }
+
+    fn request_layout(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let layout_id = self.scoped(cx, |child, cx| child.request_layout(window, cx));
+        (layout_id, ())
+    }
+
+    fn prepaint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        _bounds: Bounds<Pixels>,
+        _request_layout: &mut Self::RequestLayoutState,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self::PrepaintState {
+        self.scoped(cx, |child, cx| {
+            child.prepaint(window, cx);
+        });
+    }
+
+    fn paint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        _bounds: Bounds<Pixels>,
+        _request_layout: &mut Self::RequestLayoutState,
+        _prepaint: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        self.scoped(cx, |child, cx| child.paint(window, cx));
+    }
 }
 
-fn match_tokens(
-    tokens: &[Token],
-    path_segments: &[&str],
-    token_index: usize,
-    path_index: usize,
-    params: &mut Vec<(String, String)>,
-) -> bool {
-    if token_index == tokens.len() {
-        return path_index == path_segments.len();
-    }
-
-    match &tokens[token_index] {
-        Token::Static(expected) => {
-            let Some(actual) = path_segments.get(path_index).copied() else {
-                return false;
-            };
-            if actual != expected {
-                return false;
-            }
-            match_tokens(tokens, path_segments, token_index + 1, path_index + 1, params)
-        }
-        Token::ParamSingle(name) => {
-            let Some(value) = path_segments.get(path_index).copied() else {
-                return false;
-            };
-            params.push((name.clone(), value.to_string()));
-            let ok = match_tokens(tokens, path_segments, token_index + 1, path_index + 1, params);
-            if !ok {
-                params.pop();
-            }
-            ok
-        }
-        Token::ParamCatchAll(name) => {
-            for end in (path_index + 1..=path_segments.len()).rev() {
-                let captured = path_segments[path_index..end].join("/");
-                params.push((name.clone(), captured));
-                let ok = match_tokens(tokens, path_segments, token_index + 1, end, params);
-                if ok {
-                    return true;
-                }
-                params.pop();
-            }
-            false
+impl Render for RouterView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // Key bindings only reach the router while focus is inside it, so it
+        // takes focus when nothing else has it.
+        let focus_handle = self.focus_handle.get_or_insert_with(|| cx.focus_handle()).clone();
+        if window.focused(cx).is_none() {
+            focus_handle.focus(window);
         }
-        Token::Wildcard => {
-            if token_index + 1 == tokens.len() {
-                return true;
-            }
 
-            for end in path_index..=path_segments.len() {
-                if match_tokens(tokens, path_segments, token_index + 1, end, params) {
-                    return true;
-                }
-            }
-            false
-        }
+        self.register(cx);
+        cx.default_global::<Routers>().prune();
+        let this = cx.entity().downgrade();
+
+        let (matched_view, matched_meta) = match self.render_page(&this, window, cx) {
+            Some(rendered) => (Some(rendered.view.clone()), Some(rendered.matched.clone())),
+            None => (None, None),
+        };
+
+        let header = render_debug_header(self.route(), matched_meta.as_ref(), self.table());
+
+        div()
+            .key_context(KEY_CONTEXT)
+            .track_focus(&focus_handle)
+            .on_action(cx.listener(|this, _: &Back, _, cx| {
+                let _ = this.back(cx);
+            }))
+            .on_action(cx.listener(|this, _: &Forward, _, cx| {
+                let _ = this.forward(cx);
+            }))
+            .on_mouse_down(
+                MouseButton::Navigate(NavigationDirection::Back),
+                cx.listener(|this, _, _, cx| {
+                    let _ = this.back(cx);
+                }),
+            )
+            .on_mouse_down(
+                MouseButton::Navigate(NavigationDirection::Forward),
+                cx.listener(|this, _, _, cx| {
+                    let _ = this.forward(cx);
+                }),
+            )
+            .flex()
+            .flex_col()
+            .size_full()
+            .child(header)
+            .child(
+                div()
+                    .flex_1()
+                    .size_full()
+                    .bg(rgb(0xffffff))
+                    .when_some(matched_view.zip(matched_meta), |d, (view, matched)| {
+                        d.child(RouteScope {
+                            router: this,
+                            matched,
+                            child: view.into_any_element(),
+                        })
+                    }),
+            )
     }
 }
 
 fn render_debug_header(
     current_route: &str,
     matched: Option<&RouteMatch>,
-    table: &RouteTable<AnyView>,
+    table: &RouteTable<RouteHandler>,
 ) -> impl IntoElement {
     let matched_path = matched.map(|m| m.spec.path.as_str()).unwrap_or("(none)");
     let matched_source = matched
@@ -356,9 +953,10 @@ fn render_debug_header(
         .child(format!("params: {params_string}"))
         .child(format!("source: {matched_source}"))
         .child(format!(
-            "routes: {}  fallback: {}",
-            table.routes.len(),
-            if table.fallback.is_some() { "yes" } else { "no" }
+            "routes: {}  fallbacks: {}  layouts: {}",
+            table.routes().len(),
+            table.fallbacks().len(),
+            table.layouts().len()
         ))
         .child(div().h(px(1.0)).bg(rgb(0x2a2a2a)))
 }
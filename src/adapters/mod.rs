@@ -1 +1,2 @@
+#[cfg(feature = "gpui")]
 pub mod gpui;
@@ -1,6 +0,0 @@
-#[derive(Debug)]
-pub enum RouteError {
-    DuplicateRoute(String),
-    InvalidGrammar(String),
-    MultipleFallbacks,
-}
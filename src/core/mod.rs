@@ -1,8 +0,0 @@
-pub mod spec;
-pub mod grammar;
-pub mod table;
-pub mod errors;
-pub mod scan;
-
-pub use spec::*;
-pub use table::*;
@@ -1,20 +1,36 @@
-pub mod core;
+pub use fs_router_core as core;
 
 pub mod adapters;
 
+pub use fs_router_core::build;
+
+#[cfg(feature = "serde")]
+pub use fs_router_core::params;
+
 pub use core::{
+    Location,
     ParamSpec,
     RouteKind,
+    RouteMatch,
     RouteSpec,
     RouteTable,
 };
 
-pub use core::errors::RouteError;
+pub use core::encoding::{decode, encode_segment};
+pub use core::events::{NavigationKind, RouteChanged, RouteListeners};
+pub use core::errors::{DecodeError, NavigateError, RouteError};
 pub use core::grammar::parse_file_path;
+pub use core::guard::{GuardDecision, LeaveDecision, NavigationGuards};
+pub use core::history::History;
+pub use core::navigator::Navigator;
+pub use core::router::{build_path, build_path_with, build_url, match_route, try_match_route};
 pub use core::scan::scan_pages;
 
-pub use adapters::gpui::{
-    RouterView,
-    NavigateError,
-    RouteMatch
-};
+#[cfg(feature = "gpui")]
+pub use adapters::gpui::{RouteContext, RouteHandler, RouterHandle, RouterView};
+
+#[cfg(feature = "macros")]
+pub use fs_router_macros::routes;
+
+#[cfg(feature = "serde")]
+pub use params::ParamsError;
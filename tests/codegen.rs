@@ -0,0 +1,261 @@
+use std::fs;
+use std::path::PathBuf;
+
+use fs_router::{NavigateError, RouteError, RouteTable};
+use fs_router::build::{BuildError, Generator, render_route_enum};
+
+// `render_route_enum` output for the page trees under `tests/fixtures`,
+// checked against the generator by `generated_enums_are_up_to_date`.
+mod typed {
+    include!("fixtures/typed_route.rs");
+}
+
+mod empty {
+    include!("fixtures/empty_route.rs");
+}
+
+fn page_dir(name: &str, files: &[&str]) -> PathBuf {
+    let root = std::env::temp_dir()
+        .join(format!("fs-router-codegen-{}", std::process::id()))
+        .join(name);
+    let _ = fs::remove_dir_all(&root);
+
+    for file in files {
+        let path = root.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "pub fn page() -> crate::Page { todo!() }\n").unwrap();
+    }
+
+    root
+}
+
+#[test]
+fn declares_a_module_per_page() {
+    let root = page_dir(
+        "modules",
+        &["index.rs", "users/[id].rs", "docs/[...slug].rs"],
+    );
+    let pages = Generator::new(&root).pages().unwrap();
+
+    let modules: Vec<_> = pages.iter().map(|page| page.module.as_str()).collect();
+    assert_eq!(
+        modules,
+        vec!["page_docs_slug", "page_index", "page_users_id"]
+    );
+
+    let paths: Vec<_> = pages.iter().map(|page| page.spec.path.as_str()).collect();
+    assert_eq!(paths, vec!["/docs/:slug/*", "/", "/users/:id"]);
+}
+
+#[test]
+fn renders_route_table_with_constructors() {
+    let root = page_dir("render", &["index.rs", "users/[id].rs"]);
+    let code = Generator::new(&root)
+        .handler("crate::View")
+        .constructor("view")
+        .render()
+        .unwrap();
+
+    assert!(code.contains("pub mod page_index;"));
+    assert!(code.contains("pub mod page_users_id;"));
+    assert!(code.contains("pub fn route_table() -> ::fs_router::RouteTable<crate::View>"));
+    assert!(code.contains("page_users_id::view()"));
+    assert!(code.contains("path: \"/users/:id\".to_string()"));
+}
+
+#[test]
+fn deduplicates_module_names() {
+    let root = page_dir("dedupe", &["a-b.rs", "a_b.rs"]);
+    let pages = Generator::new(&root).pages().unwrap();
+
+    let modules: Vec<_> = pages.iter().map(|page| page.module.as_str()).collect();
+    assert_eq!(modules, vec!["page_a_b", "page_a_b_2"]);
+}
+
+#[test]
+fn rejects_invalid_tables() {
+    let root = page_dir("duplicates", &["blog.rs", "blog/index.rs"]);
+
+    match Generator::new(&root).pages().unwrap_err() {
+        BuildError::Conflict {
+            source,
+            existing,
+            error: RouteError::DuplicateRoute(path),
+        } => {
+            assert_eq!(path, "/blog");
+            assert!(source.ends_with("/blog.rs"));
+            assert!(existing.unwrap().ends_with("/blog/index.rs"));
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[test]
+fn renders_typed_route_enum() {
+    let root = page_dir(
+        "typed",
+        &[
+            "index.rs",
+            "users/[id].rs",
+            "docs/[...slug].rs",
+            "docs/_layout.rs",
+            "guides/[[...path]].rs",
+            "[[lang]]/pricing.rs",
+            "posts/[id=integer].rs",
+            "404.rs",
+        ],
+    );
+    let code = Generator::new(&root).render().unwrap();
+
+    assert!(code.contains("pub enum Route {"));
+    assert!(code.contains("    Index,\n"));
+    assert!(code.contains("    UsersId { id: String },\n"));
+    assert!(code.contains("    DocsSlug { slug: Vec<String> },\n"));
+    assert!(code.contains("    GuidesPath { path: Vec<String> },\n"));
+    assert!(code.contains("    LangPricing { lang: Option<String> },\n"));
+    assert!(code.contains("    PostsId { id: String },\n"));
+    assert!(code.contains("matcher: Some(\"integer\".to_string())"));
+    assert!(!code.contains("    Page404"));
+    assert!(!code.contains("    DocsLayout"));
+    assert!(code.contains("kind: ::fs_router::RouteKind::Layout"));
+    assert!(code.contains("pub fn to_path(&self) -> String"));
+    assert!(code.contains("pub fn from_path(path: &str) -> Option<Self>"));
+}
+
+#[test]
+fn reports_both_groups_of_a_duplicate_path() {
+    let root = page_dir("groups", &["(marketing)/about.rs", "(company)/about.rs"]);
+
+    match Generator::new(&root).pages().unwrap_err() {
+        BuildError::Conflict {
+            source,
+            existing,
+            error: RouteError::DuplicateRoute(path),
+        } => {
+            assert_eq!(path, "/about");
+            assert!(source.ends_with("/(marketing)/about.rs"));
+            assert!(existing.unwrap().ends_with("/(company)/about.rs"));
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[test]
+fn generated_enums_are_up_to_date() {
+    for (page_dir, generated) in [
+        ("tests/fixtures/typed", "tests/fixtures/typed_route.rs"),
+        ("tests/fixtures/empty", "tests/fixtures/empty_route.rs"),
+    ] {
+        let pages = Generator::new(page_dir).pages().unwrap();
+        let expected = fs::read_to_string(generated).unwrap();
+        assert_eq!(
+            render_route_enum(&pages),
+            expected,
+            "regenerate {generated} from {page_dir}"
+        );
+    }
+}
+
+#[test]
+fn typed_routes_round_trip() {
+    use typed::Route;
+
+    let routes = [
+        (Route::Index, "/"),
+        (
+            Route::PostsPostId {
+                post_id: "hello world".to_string(),
+            },
+            "/posts/hello%20world",
+        ),
+        (
+            Route::UsersUserIdType {
+                user_id: "7".to_string(),
+                r#type: "admin".to_string(),
+            },
+            "/users/7/admin",
+        ),
+        (
+            Route::DocsSlug {
+                slug: vec!["guide".to_string(), "intro".to_string()],
+            },
+            "/docs/guide/intro",
+        ),
+        (Route::ShopFilters { filters: vec![] }, "/shop"),
+        (Route::LangAbout { lang: None }, "/about"),
+        (
+            Route::OrdersId {
+                id: "42".to_string(),
+            },
+            "/orders/42",
+        ),
+    ];
+
+    for (route, path) in routes {
+        assert_eq!(route.to_path(), path);
+        assert_eq!(Route::from_path(path), Some(route));
+    }
+
+    assert_eq!(Route::from_path("/missing"), None);
+}
+
+#[test]
+fn typed_routes_check_the_app_matchers() {
+    use typed::Route;
+
+    let pages = Generator::new("tests/fixtures/typed").pages().unwrap();
+    let mut table = RouteTable::from_routes(pages.into_iter().map(|page| (page.spec, ()))).unwrap();
+    table.register_matcher("locale", |value| matches!(value, "en" | "fr"));
+
+    let french = Route::LangAbout {
+        lang: Some("fr".to_string()),
+    };
+    assert_eq!(Route::from_path("/fr/about"), None);
+    assert_eq!(
+        Route::from_match(&table.resolve("/fr/about").unwrap()),
+        Some(french.clone())
+    );
+    assert_eq!(french.to_path_with(table.matchers()).unwrap(), "/fr/about");
+
+    let german = Route::LangAbout {
+        lang: Some("de".to_string()),
+    };
+    assert_eq!(german.to_path(), "/de/about");
+    assert_eq!(
+        german.to_path_with(table.matchers()),
+        Err(NavigateError::InvalidParam {
+            name: "lang".to_string(),
+            matcher: "locale".to_string(),
+        })
+    );
+
+    let order = Route::OrdersId {
+        id: "abc".to_string(),
+    };
+    assert!(matches!(
+        order.to_path_with(table.matchers()),
+        Err(NavigateError::InvalidParam { .. })
+    ));
+}
+
+#[test]
+fn pages_without_routes_generate_an_empty_enum() {
+    assert_eq!(empty::Route::from_path("/"), None);
+}
+
+#[test]
+fn param_names_become_snake_case_fields() {
+    let root = page_dir("fields", &["[post-id]/[userId]/[self].rs"]);
+    let code = Generator::new(&root).render().unwrap();
+
+    assert!(code.contains("PostIdUserIdSelf { post_id: String, user_id: String, self_: String }"));
+
+    let root = page_dir("same-field", &["[post-id]/[post_id].rs"]);
+    match Generator::new(&root).pages().unwrap_err() {
+        BuildError::DuplicateField { source, field } => {
+            assert!(source.ends_with("/[post-id]/[post_id].rs"));
+            assert_eq!(field, "post_id");
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
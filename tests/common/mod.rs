@@ -0,0 +1,6 @@
+use fs_router::RouteSpec;
+
+/// Parses a page file the way the build script would.
+pub fn page(source: &str) -> RouteSpec {
+    fs_router::parse_file_path(source, None).unwrap()
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::page;
+use fs_router::{
+    DecodeError, RouteTable, build_path, decode, encode_segment, match_route, try_match_route,
+};
+
+#[test]
+fn encodes_reserved_characters() {
+    assert_eq!(encode_segment("hello world"), "hello%20world");
+    assert_eq!(encode_segment("a/b?c#d%"), "a%2Fb%3Fc%23d%25");
+    assert_eq!(encode_segment("café"), "caf%C3%A9");
+    assert_eq!(encode_segment("post-1.rs~"), "post-1.rs~");
+}
+
+#[test]
+fn build_path_encodes_params() {
+    let user = page("pages/users/[name].rs");
+    assert_eq!(
+        build_path(&user, &[("name", "ada lovelace")]).unwrap(),
+        "/users/ada%20lovelace"
+    );
+    assert_eq!(build_path(&user, &[("name", "a/b")]).unwrap(), "/users/a%2Fb");
+
+    let docs = page("pages/docs/[...slug].rs");
+    assert_eq!(
+        build_path(&docs, &[("slug", "getting started/100%")]).unwrap(),
+        "/docs/getting%20started/100%25"
+    );
+}
+
+#[test]
+fn match_route_decodes_captured_values() {
+    let user = page("pages/users/[name].rs");
+
+    let matched = match_route(&user, "/users/ada%20lovelace").unwrap();
+    assert_eq!(matched.param("name"), Some("ada lovelace"));
+
+    let matched = match_route(&user, "/users/a%2Fb").unwrap();
+    assert_eq!(matched.param("name"), Some("a/b"));
+
+    let path = build_path(&user, &[("name", "café?")]).unwrap();
+    let matched = match_route(&user, &path).unwrap();
+    assert_eq!(matched.param("name"), Some("café?"));
+
+    let docs = page("pages/docs/[...slug].rs");
+    let matched = match_route(&docs, "/docs/getting%20started/intro").unwrap();
+    assert_eq!(matched.param("slug"), Some("getting started/intro"));
+}
+
+#[test]
+fn static_segments_match_decoded() {
+    let about = page("pages/über.rs");
+    let id = about.id;
+    let table = RouteTable::from_routes([(about, "about")]).unwrap();
+
+    assert_eq!(table.resolve("/%C3%BCber").unwrap().spec.id, id);
+    assert_eq!(table.path_for(id, &[]).unwrap(), "/%C3%BCber");
+}
+
+#[test]
+fn reports_malformed_escapes() {
+    let user = page("pages/users/[name].rs");
+
+    assert_eq!(
+        try_match_route(&user, "/users/100%").unwrap_err(),
+        DecodeError::MalformedEscape {
+            input: "100%".to_string(),
+            position: 3,
+        }
+    );
+    assert!(matches!(
+        try_match_route(&user, "/users/%zz"),
+        Err(DecodeError::MalformedEscape { .. })
+    ));
+    assert_eq!(
+        decode("%FF").unwrap_err().to_string(),
+        "invalid utf-8 after decoding : %FF"
+    );
+    assert!(match_route(&user, "/users/%FF").is_none());
+
+    let table = RouteTable::from_routes([(user, "user")]).unwrap();
+    assert!(table.resolve("/users/%E2%82").is_none());
+    assert_eq!(
+        table.try_resolve("/users/%E2%82").unwrap_err(),
+        DecodeError::InvalidUtf8 {
+            input: "%E2%82".to_string(),
+        }
+    );
+    assert!(table.try_resolve("/users/ok").unwrap().is_some());
+}
+
+#[test]
+fn bad_query_escapes_do_not_affect_matching() {
+    let search = page("pages/search.rs");
+    let table = RouteTable::from_routes([(search.clone(), "search")]).unwrap();
+
+    let matched = table.try_resolve("/search?q=100%").unwrap().unwrap();
+    assert_eq!(matched.spec.id, search.id);
+    assert_eq!(matched.query_param("q"), Some("100%"));
+
+    assert!(match_route(&search, "/search#%zz").is_some());
+}
+
+#[test]
+fn undecodable_paths_fall_back() {
+    let table = RouteTable::from_routes(
+        ["pages/users/[id].rs", "pages/users/404.rs", "pages/404.rs"]
+            .map(|source| (page(source), source)),
+    )
+    .unwrap();
+
+    assert!(table.resolve("/users/%zz").is_none());
+    let (matched, handler) = table.resolve_or_fallback("/users/%zz?tab=1").unwrap();
+    assert_eq!(*handler, "pages/users/404.rs");
+    assert_eq!(matched.query_param("tab"), Some("1"));
+    assert_eq!(table.resolve_or_fallback("/%FF").unwrap().1, &"pages/404.rs");
+}
@@ -0,0 +1,66 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fs_router::{NavigationKind, RouteChanged, RouteListeners, RouteTable};
+
+fn table() -> RouteTable<&'static str> {
+    RouteTable::from_routes(
+        ["pages/index.rs", "pages/users/[id].rs", "pages/404.rs"]
+            .map(|source| (fs_router::parse_file_path(source, None).unwrap(), source)),
+    )
+    .unwrap()
+}
+
+#[test]
+fn resolves_both_ends() {
+    let changed =
+        RouteChanged::between(&table(), "/", "/users/7?tab=posts", NavigationKind::Push).unwrap();
+
+    assert_eq!(changed.from.unwrap().spec.path, "/");
+    assert_eq!(changed.to.param("id"), Some("7"));
+    assert_eq!(changed.to.query_param("tab"), Some("posts"));
+    assert_eq!(changed.kind, NavigationKind::Push);
+}
+
+#[test]
+fn falls_back_like_the_router() {
+    let table = table();
+
+    let changed = RouteChanged::between(&table, "/users/7", "/missing", NavigationKind::Replace)
+        .unwrap();
+    assert_eq!(changed.to.spec.path, "/*");
+
+    let empty: RouteTable<()> = RouteTable::new();
+    assert!(RouteChanged::between(&empty, "/", "/missing", NavigationKind::Push).is_none());
+}
+
+#[test]
+fn history_moves_map_to_back_and_forward() {
+    assert_eq!(NavigationKind::from_delta(-1), NavigationKind::Back);
+    assert_eq!(NavigationKind::from_delta(-3), NavigationKind::Back);
+    assert_eq!(NavigationKind::from_delta(2), NavigationKind::Forward);
+}
+
+#[test]
+fn listeners_receive_events_until_unsubscribed() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut listeners = RouteListeners::new();
+
+    let log = seen.clone();
+    let id = listeners.subscribe(move |changed| log.borrow_mut().push(changed.to.spec.path.clone()));
+    let log = seen.clone();
+    listeners.subscribe(move |changed| log.borrow_mut().push(format!("{:?}", changed.kind)));
+    assert_eq!(listeners.len(), 2);
+
+    let table = table();
+    let changed = RouteChanged::between(&table, "/", "/users/1", NavigationKind::Push).unwrap();
+    listeners.emit(&changed);
+    assert_eq!(*seen.borrow(), ["/users/:id", "Push"]);
+
+    assert!(listeners.unsubscribe(id));
+    assert!(!listeners.unsubscribe(id));
+
+    let changed = RouteChanged::between(&table, "/users/1", "/", NavigationKind::Back).unwrap();
+    listeners.emit(&changed);
+    assert_eq!(*seen.borrow(), ["/users/:id", "Push", "Back"]);
+}
@@ -0,0 +1,34 @@
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Route {}
+
+#[allow(dead_code)]
+impl Route {
+    pub fn spec(&self) -> &'static ::fs_router::RouteSpec {
+        match *self {}
+    }
+
+    pub fn id(&self) -> u64 {
+        match *self {}
+    }
+
+    pub fn to_path(&self) -> String {
+        match *self {}
+    }
+
+    pub fn to_path_with(
+        &self,
+        _matchers: &::fs_router::core::param_matcher::ParamMatchers,
+    ) -> Result<String, ::fs_router::NavigateError> {
+        match *self {}
+    }
+
+    pub fn from_path(_path: &str) -> Option<Self> {
+        None
+    }
+
+    pub fn from_match(_matched: &::fs_router::RouteMatch) -> Option<Self> {
+        None
+    }
+}
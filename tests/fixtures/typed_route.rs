@@ -0,0 +1,105 @@
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Route {
+    LangAbout { lang: Option<String> },
+    DocsSlug { slug: Vec<String> },
+    Index,
+    OrdersId { id: String },
+    PostsPostId { post_id: String },
+    ShopFilters { filters: Vec<String> },
+    UsersUserIdType { user_id: String, r#type: String },
+}
+
+#[allow(dead_code)]
+fn route_specs() -> &'static ::fs_router::RouteTable<usize> {
+    static TABLE: ::std::sync::OnceLock<::fs_router::RouteTable<usize>> = ::std::sync::OnceLock::new();
+    TABLE.get_or_init(|| {
+        ::fs_router::RouteTable::from_routes([
+            (::fs_router::RouteSpec { id: 9642296384966967028, path: "/:lang=locale?/about".to_string(), kind: ::fs_router::RouteKind::Dynamic, params: vec![::fs_router::ParamSpec { name: "lang".to_string(), kind: ::fs_router::core::ParamKind::Optional, matcher: Some("locale".to_string()) }], source: "tests/fixtures/typed/[[lang=locale]]/about.rs".to_string(), layouts: vec![16511729842514179736, 598891536484052830] }, 0usize),
+            (::fs_router::RouteSpec { id: 8720310018886814600, path: "/docs/:slug/*".to_string(), kind: ::fs_router::RouteKind::CatchAll, params: vec![::fs_router::ParamSpec { name: "slug".to_string(), kind: ::fs_router::core::ParamKind::CatchAll, matcher: None }], source: "tests/fixtures/typed/docs/[...slug].rs".to_string(), layouts: vec![16511729842514179736, 5919578214254127358] }, 1usize),
+            (::fs_router::RouteSpec { id: 9497966886403524235, path: "/".to_string(), kind: ::fs_router::RouteKind::Static, params: vec![], source: "tests/fixtures/typed/index.rs".to_string(), layouts: vec![16511729842514179736] }, 2usize),
+            (::fs_router::RouteSpec { id: 10030666476144945475, path: "/orders/:id=integer".to_string(), kind: ::fs_router::RouteKind::Dynamic, params: vec![::fs_router::ParamSpec { name: "id".to_string(), kind: ::fs_router::core::ParamKind::Single, matcher: Some("integer".to_string()) }], source: "tests/fixtures/typed/orders/[id=integer].rs".to_string(), layouts: vec![16511729842514179736, 7620083880832396748] }, 3usize),
+            (::fs_router::RouteSpec { id: 15988934056287068941, path: "/posts/:post-id".to_string(), kind: ::fs_router::RouteKind::Dynamic, params: vec![::fs_router::ParamSpec { name: "post-id".to_string(), kind: ::fs_router::core::ParamKind::Single, matcher: None }], source: "tests/fixtures/typed/posts/[post-id].rs".to_string(), layouts: vec![16511729842514179736, 4326834269422103772] }, 4usize),
+            (::fs_router::RouteSpec { id: 4632564190521178851, path: "/shop/:filters/*?".to_string(), kind: ::fs_router::RouteKind::CatchAll, params: vec![::fs_router::ParamSpec { name: "filters".to_string(), kind: ::fs_router::core::ParamKind::OptionalCatchAll, matcher: None }], source: "tests/fixtures/typed/shop/[[...filters]].rs".to_string(), layouts: vec![16511729842514179736, 13307111777441936781] }, 5usize),
+            (::fs_router::RouteSpec { id: 14059917809536791799, path: "/users/:userId/:type".to_string(), kind: ::fs_router::RouteKind::Dynamic, params: vec![::fs_router::ParamSpec { name: "userId".to_string(), kind: ::fs_router::core::ParamKind::Single, matcher: None }, ::fs_router::ParamSpec { name: "type".to_string(), kind: ::fs_router::core::ParamKind::Single, matcher: None }], source: "tests/fixtures/typed/users/[userId]/[type].rs".to_string(), layouts: vec![16511729842514179736, 5695537740157247915, 12407458929635700586] }, 6usize),
+        ])
+        .expect("routes are validated when generated")
+    })
+}
+
+#[allow(dead_code)]
+impl Route {
+    pub fn spec(&self) -> &'static ::fs_router::RouteSpec {
+        let index = match self {
+            Route::LangAbout { .. } => 0,
+            Route::DocsSlug { .. } => 1,
+            Route::Index => 2,
+            Route::OrdersId { .. } => 3,
+            Route::PostsPostId { .. } => 4,
+            Route::ShopFilters { .. } => 5,
+            Route::UsersUserIdType { .. } => 6,
+        };
+        &route_specs().routes()[index].0
+    }
+
+    pub fn id(&self) -> u64 {
+        self.spec().id
+    }
+
+    /// The route's path. Params are not checked against their matchers,
+    /// see `to_path_with`.
+    pub fn to_path(&self) -> String {
+        self.build_with(::fs_router::build_path)
+            .expect("typed routes supply every param")
+    }
+
+    /// The route's path, or `InvalidParam` when a param does not satisfy its
+    /// matcher in `matchers`, e.g. the app table's `matchers()`.
+    pub fn to_path_with(
+        &self,
+        matchers: &::fs_router::core::param_matcher::ParamMatchers,
+    ) -> Result<String, ::fs_router::NavigateError> {
+        self.build_with(|spec, params| ::fs_router::build_path_with(spec, params, matchers))
+    }
+
+    fn build_with(
+        &self,
+        build: impl Fn(&::fs_router::RouteSpec, &[(&str, &str)]) -> Result<String, ::fs_router::NavigateError>,
+    ) -> Result<String, ::fs_router::NavigateError> {
+        match self {
+            Route::LangAbout { lang } => { build(self.spec(), &[("lang", lang.as_deref().unwrap_or_default())]) }
+            Route::DocsSlug { slug } => { let slug = slug.join("/"); build(self.spec(), &[("slug", slug.as_str())]) }
+            Route::Index => { build(self.spec(), &[]) }
+            Route::OrdersId { id } => { build(self.spec(), &[("id", id.as_str())]) }
+            Route::PostsPostId { post_id } => { build(self.spec(), &[("post-id", post_id.as_str())]) }
+            Route::ShopFilters { filters } => { let filters = filters.join("/"); build(self.spec(), &[("filters", filters.as_str())]) }
+            Route::UsersUserIdType { user_id, r#type } => { build(self.spec(), &[("userId", user_id.as_str()), ("type", r#type.as_str())]) }
+        }
+    }
+
+    /// Resolves `path` with the built-in param matchers only. With custom
+    /// matchers, resolve against the app's table and use `from_match`.
+    pub fn from_path(path: &str) -> Option<Self> {
+        Self::from_match(&route_specs().resolve(path)?)
+    }
+
+    /// The route of a match from any table built from these pages.
+    pub fn from_match(matched: &::fs_router::RouteMatch) -> Option<Self> {
+        let (_, index) = route_specs()
+            .routes()
+            .iter()
+            .find(|(spec, _)| spec.id == matched.spec.id)?;
+        let route = match *index {
+            0 => Route::LangAbout { lang: matched.param("lang").map(str::to_string) },
+            1 => Route::DocsSlug { slug: matched.param("slug")?.split('/').map(str::to_string).collect() },
+            2 => Route::Index,
+            3 => Route::OrdersId { id: matched.param("id")?.to_string() },
+            4 => Route::PostsPostId { post_id: matched.param("post-id")?.to_string() },
+            5 => Route::ShopFilters { filters: matched.param("filters").map(|value| value.split('/').map(str::to_string).collect()).unwrap_or_default() },
+            6 => Route::UsersUserIdType { user_id: matched.param("userId")?.to_string(), r#type: matched.param("type")?.to_string() },
+            _ => return None,
+        };
+        Some(route)
+    }
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "gpui")]
+
 use fs_router::core::{ParamKind, ParamSpec, RouteKind, RouteSpec, RouteTable};
 
 use fs_router::adapters::gpui::{NavigateError, build_path, match_route};
@@ -9,6 +11,7 @@ fn spec(path: &str, kind: RouteKind, params: Vec<ParamSpec>) -> RouteSpec {
         kind,
         params,
         source: "src".to_string(),
+        layouts: Vec::new(),
     }
 }
 
@@ -19,6 +22,7 @@ fn spec_with_id(id: u64, path: &str, kind: RouteKind, params: Vec<ParamSpec>) ->
         kind,
         params,
         source: "src".to_string(),
+        layouts: Vec::new(),
     }
 }
 
@@ -38,6 +42,7 @@ fn matches_dynamic_single() {
         vec![ParamSpec {
             name: "id".to_string(),
             kind: ParamKind::Single,
+            matcher: None,
         }],
     );
 
@@ -53,6 +58,7 @@ fn matches_dynamic_catchall_with_suffix() {
         vec![ParamSpec {
             name: "filepath".to_string(),
             kind: ParamKind::CatchAll,
+            matcher: None,
         }],
     );
 
@@ -73,10 +79,11 @@ fn does_not_match_when_segment_mismatch() {
 fn route_table_stores_fallback_separately() {
     let mut table: RouteTable<i32> = RouteTable::new();
     table
-        .insert(spec("//*", RouteKind::Fallback, vec![]), 42)
+        .insert(spec("/*", RouteKind::Fallback, vec![]), 42)
         .unwrap();
-    assert_eq!(table.fallback, Some(42));
-    assert!(table.routes.is_empty());
+    assert_eq!(table.fallbacks().len(), 1);
+    assert_eq!(table.fallbacks()[0].1, 42);
+    assert!(table.routes().is_empty());
 }
 
 #[test]
@@ -84,7 +91,7 @@ fn navigate_by_id_builds_path() {
     // We don't need a real GPUI view for this test; `AnyView` is not required.
     // We just validate the underlying path building through a public API surface.
 
-    // NOTE: `navigate_by_id` lives on `RouterView` which holds `AnyView`.
+    // NOTE: `navigate_by_id` lives on `RouterView` which holds view factories.
     // We can still validate the error paths and path construction by calling
     // `build_path`-driven behavior indirectly via matching.
 
@@ -96,6 +103,7 @@ fn navigate_by_id_builds_path() {
         vec![ParamSpec {
             name: "id".to_string(),
             kind: ParamKind::Single,
+            matcher: None,
         }],
     );
 
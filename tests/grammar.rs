@@ -1,9 +1,9 @@
 use fs_router::core::errors::RouteError;
-use fs_router::core::grammar::parse_file_path;
+use fs_router::core::grammar::{parse_file_path, route_id};
 use fs_router::core::{ParamKind, ParamSpec, RouteKind};
 
 fn id_for(inner: &str) -> u64 {
-    inner.chars().map(|c| c as u64).sum()
+    route_id(inner)
 }
 
 #[test]
@@ -32,12 +32,13 @@ fn parses_dynamic_segment() {
     let spec = parse_file_path("pages/users/[id].rs", None).unwrap();
 
     assert_eq!(spec.path, "/users/:id");
-    assert_eq!(spec.kind, RouteKind::Static);
+    assert_eq!(spec.kind, RouteKind::Dynamic);
     assert_eq!(
         spec.params,
         vec![ParamSpec {
             name: "id".to_string(),
-            kind: ParamKind::Single
+            kind: ParamKind::Single,
+            matcher: None
         }]
     );
 }
@@ -46,17 +47,19 @@ fn parses_dynamic_segment() {
 fn parses_multiple_dynamic_segments() {
     let spec = parse_file_path("pages/shop/[category]/[item_id].rs", None).unwrap();
     assert_eq!(spec.path, "/shop/:category/:item_id");
-    assert_eq!(spec.kind, RouteKind::Static);
+    assert_eq!(spec.kind, RouteKind::Dynamic);
     assert_eq!(
         spec.params,
         vec![
             ParamSpec {
                 name: "category".to_string(),
-                kind: ParamKind::Single
+                kind: ParamKind::Single,
+                matcher: None
             },
             ParamSpec {
                 name: "item_id".to_string(),
-                kind: ParamKind::Single
+                kind: ParamKind::Single,
+                matcher: None
             }
         ]
     );
@@ -67,12 +70,13 @@ fn parses_catchall_segment() {
     let spec = parse_file_path("pages/docs/[...slug].rs", None).unwrap();
 
     assert_eq!(spec.path, "/docs/:slug/*");
-    assert_eq!(spec.kind, RouteKind::Static);
+    assert_eq!(spec.kind, RouteKind::CatchAll);
     assert_eq!(
         spec.params,
         vec![ParamSpec {
             name: "slug".to_string(),
-            kind: ParamKind::CatchAll
+            kind: ParamKind::CatchAll,
+            matcher: None
         }]
     );
 }
@@ -82,12 +86,13 @@ fn parses_catchall_within_path() {
     let spec = parse_file_path("pages/files/[...filepath]/info.rs", None).unwrap();
 
     assert_eq!(spec.path, "/files/:filepath/*/info");
-    assert_eq!(spec.kind, RouteKind::Static);
+    assert_eq!(spec.kind, RouteKind::CatchAll);
     assert_eq!(
         spec.params,
         vec![ParamSpec {
             name: "filepath".to_string(),
-            kind: ParamKind::CatchAll
+            kind: ParamKind::CatchAll,
+            matcher: None
         }]
     );
 }
@@ -96,7 +101,7 @@ fn parses_catchall_within_path() {
 fn parses_fallback_404() {
     let spec = parse_file_path("pages/404.rs", None).unwrap();
 
-    assert_eq!(spec.path, "//*");
+    assert_eq!(spec.path, "/*");
     assert_eq!(spec.kind, RouteKind::Fallback);
     assert!(spec.params.is_empty());
     assert_eq!(spec.id, id_for("404"));
@@ -134,3 +139,196 @@ fn errors_when_not_rust_file() {
         _ => panic!("expected InvalidGrammar"),
     }
 }
+
+#[test]
+fn ids_do_not_collide_on_anagrams() {
+    let ab = parse_file_path("pages/ab.rs", None).unwrap();
+    let ba = parse_file_path("pages/ba.rs", None).unwrap();
+
+    assert_ne!(ab.id, ba.id);
+}
+
+#[test]
+fn ids_are_stable() {
+    assert_eq!(route_id(""), 0xcbf29ce484222325);
+    assert_eq!(route_id("a"), 0xaf63dc4c8601ec8c);
+}
+
+#[test]
+fn parses_nested_index_to_directory() {
+    let spec = parse_file_path("pages/blog/index.rs", None).unwrap();
+
+    assert_eq!(spec.path, "/blog");
+    assert_eq!(spec.source, "pages/blog/index.rs");
+    assert_eq!(spec.id, id_for("blog/index"));
+
+    let spec = parse_file_path("pages/users/[id]/index.rs", None).unwrap();
+    assert_eq!(spec.path, "/users/:id");
+}
+
+#[test]
+fn index_only_collapses_as_the_last_segment() {
+    let spec = parse_file_path("pages/index/about.rs", None).unwrap();
+    assert_eq!(spec.path, "/index/about");
+}
+
+#[test]
+fn parses_nested_fallback_404() {
+    let spec = parse_file_path("pages/docs/404.rs", None).unwrap();
+
+    assert_eq!(spec.path, "/docs/*");
+    assert_eq!(spec.kind, RouteKind::Fallback);
+}
+
+#[test]
+fn kind_reflects_the_most_dynamic_segment() {
+    let spec = parse_file_path("pages/[org]/docs/[...slug].rs", None).unwrap();
+    assert_eq!(spec.kind, RouteKind::CatchAll);
+
+    let spec = parse_file_path("pages/[...path]/[id].rs", None).unwrap();
+    assert_eq!(spec.kind, RouteKind::CatchAll);
+
+    let spec = parse_file_path("pages/users/[id]/404.rs", None).unwrap();
+    assert_eq!(spec.kind, RouteKind::Fallback);
+}
+
+#[test]
+fn parses_layout_files() {
+    let spec = parse_file_path("pages/settings/_layout.rs", None).unwrap();
+
+    assert_eq!(spec.path, "/settings/*");
+    assert_eq!(spec.kind, RouteKind::Layout);
+    assert_eq!(spec.id, id_for("settings/_layout"));
+    assert_eq!(spec.layouts, vec![id_for("_layout")]);
+}
+
+#[test]
+fn records_layout_chain_outermost_first() {
+    let spec = parse_file_path("pages/settings/profile/[tab].rs", None).unwrap();
+
+    assert_eq!(
+        spec.layouts,
+        vec![
+            id_for("_layout"),
+            id_for("settings/_layout"),
+            id_for("settings/profile/_layout"),
+        ]
+    );
+
+    let spec = parse_file_path("pages/settings.rs", None).unwrap();
+    assert_eq!(spec.layouts, vec![id_for("_layout")]);
+}
+
+#[test]
+fn route_groups_add_nothing_to_the_path() {
+    let spec = parse_file_path("pages/(marketing)/about.rs", None).unwrap();
+
+    assert_eq!(spec.path, "/about");
+    assert_eq!(spec.kind, RouteKind::Static);
+    assert_eq!(spec.id, id_for("(marketing)/about"));
+    assert_eq!(
+        spec.layouts,
+        vec![id_for("_layout"), id_for("(marketing)/_layout")]
+    );
+
+    let spec = parse_file_path("pages/(shop)/(cart)/index.rs", None).unwrap();
+    assert_eq!(spec.path, "/");
+
+    let spec = parse_file_path("pages/(admin)/_layout.rs", None).unwrap();
+    assert_eq!(spec.path, "/*");
+    assert_eq!(spec.kind, RouteKind::Layout);
+}
+
+#[test]
+fn only_directories_are_route_groups() {
+    let spec = parse_file_path("pages/(about).rs", None).unwrap();
+    assert_eq!(spec.path, "/(about)");
+}
+
+#[test]
+fn parses_optional_catchall() {
+    let spec = parse_file_path("pages/docs/[[...slug]].rs", None).unwrap();
+
+    assert_eq!(spec.path, "/docs/:slug/*?");
+    assert_eq!(spec.kind, RouteKind::CatchAll);
+    assert_eq!(spec.params[0].name, "slug");
+    assert_eq!(spec.params[0].kind, ParamKind::OptionalCatchAll);
+}
+
+#[test]
+fn parses_optional_segment() {
+    let spec = parse_file_path("pages/[[lang]]/pricing.rs", None).unwrap();
+
+    assert_eq!(spec.path, "/:lang?/pricing");
+    assert_eq!(spec.kind, RouteKind::Dynamic);
+    assert_eq!(spec.params[0].name, "lang");
+    assert_eq!(spec.params[0].kind, ParamKind::Optional);
+}
+
+#[test]
+fn parses_param_matchers() {
+    let spec = parse_file_path("pages/users/[id=integer].rs", None).unwrap();
+
+    assert_eq!(spec.path, "/users/:id=integer");
+    assert_eq!(spec.params[0].name, "id");
+    assert_eq!(spec.params[0].matcher.as_deref(), Some("integer"));
+
+    let spec = parse_file_path("pages/docs/[...path=slug].rs", None).unwrap();
+    assert_eq!(spec.path, "/docs/:path=slug/*");
+    assert_eq!(spec.params[0].kind, ParamKind::CatchAll);
+    assert_eq!(spec.params[0].matcher.as_deref(), Some("slug"));
+}
+
+#[test]
+fn parses_mixed_segments() {
+    let spec = parse_file_path("pages/posts/post-[id].rs", None).unwrap();
+
+    assert_eq!(spec.path, "/posts/post-{id}");
+    assert_eq!(spec.kind, RouteKind::Dynamic);
+    assert_eq!(spec.params.len(), 1);
+    assert_eq!(spec.params[0].name, "id");
+    assert_eq!(spec.params[0].kind, ParamKind::Single);
+
+    let spec = parse_file_path("pages/files/[name].[ext=slug].rs", None).unwrap();
+    assert_eq!(spec.path, "/files/{name}.{ext=slug}");
+    assert_eq!(spec.params[0].name, "name");
+    assert_eq!(spec.params[1].name, "ext");
+    assert_eq!(spec.params[1].matcher.as_deref(), Some("slug"));
+}
+
+#[test]
+fn rejects_malformed_mixed_segments() {
+    for path in [
+        "pages/posts/post-[id.rs",
+        "pages/posts/post-[].rs",
+        "pages/posts/post-[...rest].rs",
+        "pages/posts/post-[[lang]].rs",
+        "pages/posts/{id}.rs",
+        "pages/{x}/index.rs",
+        "pages/posts/post-[id]}.rs",
+    ] {
+        match parse_file_path(path, None) {
+            Err(RouteError::InvalidGrammar(message)) => assert!(message.contains(path)),
+            other => panic!("expected InvalidGrammar for {path}, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn rejects_empty_param_and_matcher_names() {
+    for path in [
+        "pages/users/[].rs",
+        "pages/users/[[]].rs",
+        "pages/docs/[...].rs",
+        "pages/docs/[[...]].rs",
+        "pages/users/[=integer].rs",
+        "pages/users/[id=].rs",
+        "pages/users/[[=slug]]/index.rs",
+        "pages/posts/post-[=integer].rs",
+    ] {
+        match parse_file_path(path, None) {
+            Err(RouteError::InvalidGrammar(message)) => assert!(message.contains(path)),
+            other => panic!("expected InvalidGrammar for {path}, got {other:?}"),
+        }
+    }
+}
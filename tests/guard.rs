@@ -0,0 +1,93 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use fs_router::{GuardDecision, Location, NavigateError, NavigationGuards};
+
+#[test]
+fn allows_without_guards() {
+    let guards = NavigationGuards::new();
+
+    assert!(guards.is_empty());
+    assert_eq!(guards.run("/", "/settings").unwrap(), "/settings");
+}
+
+#[test]
+fn prefix_guards_redirect_to_login() {
+    let signed_in = Rc::new(Cell::new(false));
+    let mut guards = NavigationGuards::new();
+
+    let state = signed_in.clone();
+    guards.before_navigate_at("/admin/*", move |_, to| {
+        if state.get() {
+            GuardDecision::Allow
+        } else {
+            let login = Location::new("/login").with_query("next", to.to_string());
+            GuardDecision::Redirect(login.to_string())
+        }
+    });
+
+    assert_eq!(guards.run("/", "/admin").unwrap(), "/login?next=/admin");
+    assert_eq!(guards.run("/", "/administer").unwrap(), "/administer");
+
+    let login = guards.run("/", "/admin/users?tab=a&page=2").unwrap();
+    assert_eq!(login, "/login?next=/admin/users?tab%3Da%26page%3D2");
+    assert_eq!(
+        Location::parse(&login).query_param("next"),
+        Some("/admin/users?tab=a&page=2")
+    );
+
+    signed_in.set(true);
+    assert_eq!(guards.run("/", "/admin/users").unwrap(), "/admin/users");
+}
+
+#[test]
+fn prefix_guards_match_decoded_paths() {
+    let mut guards = NavigationGuards::new();
+    guards.before_navigate_at("/admin", |_, _| GuardDecision::Redirect("/login".to_string()));
+
+    assert_eq!(guards.run("/", "/%61dmin/users").unwrap(), "/login");
+    assert_eq!(guards.run("/", "/%61%64%6D%69%6E").unwrap(), "/login");
+    assert_eq!(guards.run("/", "/%zz/users").unwrap(), "/login");
+    assert_eq!(guards.run("/", "/admin%2Fusers").unwrap(), "/admin%2Fusers");
+}
+
+#[test]
+fn guards_see_both_locations() {
+    let mut guards = NavigationGuards::new();
+    guards.before_navigate(|from, to| {
+        if from.path == "/editor" && to.query_param("force").is_none() {
+            GuardDecision::Cancel
+        } else {
+            GuardDecision::Allow
+        }
+    });
+
+    assert_eq!(guards.run("/editor", "/"), Err(NavigateError::Cancelled));
+    assert_eq!(guards.run("/editor", "/?force").unwrap(), "/?force");
+    assert_eq!(guards.run("/home", "/").unwrap(), "/");
+}
+
+#[test]
+fn redirects_rerun_guards() {
+    let mut guards = NavigationGuards::new();
+    guards.before_navigate_at("/old", |_, _| GuardDecision::Redirect("/new".to_string()));
+    guards.before_navigate_at("/new", |_, _| GuardDecision::Redirect("/newer".to_string()));
+
+    assert_eq!(guards.run("/", "/old").unwrap(), "/newer");
+}
+
+#[test]
+fn redirect_cycles_hit_the_limit() {
+    let mut guards = NavigationGuards::new();
+    guards.before_navigate_at("/a", |_, _| GuardDecision::Redirect("/b".to_string()));
+    guards.before_navigate_at("/b", |_, _| GuardDecision::Redirect("/a".to_string()));
+    guards.set_max_redirects(3);
+
+    assert_eq!(
+        guards.run("/", "/a"),
+        Err(NavigateError::TooManyRedirects {
+            path: "/a".to_string(),
+            limit: 3,
+        })
+    );
+}
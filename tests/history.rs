@@ -0,0 +1,75 @@
+use fs_router::History;
+
+#[test]
+fn starts_at_initial_entry() {
+    let history = History::new("/");
+    assert_eq!(history.current(), "/");
+    assert!(!history.can_go_back());
+    assert!(!history.can_go_forward());
+}
+
+#[test]
+fn back_and_forward_walk_entries() {
+    let mut history = History::new("/");
+    history.push("/blog");
+    history.push("/blog/hello");
+
+    assert_eq!(history.back(), Some("/blog"));
+    assert_eq!(history.back(), Some("/"));
+    assert_eq!(history.back(), None);
+    assert_eq!(history.current(), "/");
+
+    assert_eq!(history.forward(), Some("/blog"));
+    assert!(history.can_go_forward());
+    assert_eq!(history.forward(), Some("/blog/hello"));
+    assert_eq!(history.forward(), None);
+}
+
+#[test]
+fn push_after_back_drops_forward_entries() {
+    let mut history = History::new("/");
+    history.push("/a");
+    history.push("/b");
+    history.back();
+    history.push("/c");
+
+    assert_eq!(history.entries(), ["/", "/a", "/c"]);
+    assert!(!history.can_go_forward());
+}
+
+#[test]
+fn replace_keeps_position() {
+    let mut history = History::new("/");
+    history.push("/login");
+    history.replace("/dashboard");
+
+    assert_eq!(history.entries(), ["/", "/dashboard"]);
+    assert_eq!(history.index(), 1);
+}
+
+#[test]
+fn go_jumps_by_delta() {
+    let mut history = History::new("/");
+    history.push("/a");
+    history.push("/b");
+    history.push("/c");
+
+    assert_eq!(history.go(-3), Some("/"));
+    assert_eq!(history.go(2), Some("/b"));
+    assert_eq!(history.go(5), None);
+    assert_eq!(history.go(0), None);
+    assert_eq!(history.current(), "/b");
+}
+
+#[test]
+fn max_depth_drops_oldest_entries() {
+    let mut history = History::with_max_depth("/", 3);
+    history.push("/a");
+    history.push("/b");
+    history.push("/c");
+
+    assert_eq!(history.entries(), ["/a", "/b", "/c"]);
+    assert_eq!(history.current(), "/c");
+    assert_eq!(history.go(-2), Some("/a"));
+    assert!(!history.can_go_back());
+}
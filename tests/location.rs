@@ -0,0 +1,69 @@
+use fs_router::Location;
+
+#[test]
+fn parses_path_query_and_fragment() {
+    let location = Location::parse("/search?q=rust&page=2&debug#top");
+
+    assert_eq!(location.path, "/search");
+    assert_eq!(
+        location.query,
+        vec![
+            ("q".to_string(), "rust".to_string()),
+            ("page".to_string(), "2".to_string()),
+            ("debug".to_string(), String::new()),
+        ]
+    );
+    assert_eq!(location.query_param("page"), Some("2"));
+    assert_eq!(location.fragment.as_deref(), Some("top"));
+}
+
+#[test]
+fn parses_plain_paths() {
+    let location = Location::parse("/users/7");
+
+    assert_eq!(location, Location::new("/users/7"));
+    assert!(location.query.is_empty());
+    assert_eq!(location.fragment, None);
+
+    let location = Location::parse("/docs#");
+    assert_eq!(location.path, "/docs");
+    assert_eq!(location.fragment.as_deref(), Some(""));
+}
+
+#[test]
+fn displays_as_a_url() {
+    let location = Location::new("/search")
+        .with_query("q", "rust")
+        .with_query("all", "")
+        .with_fragment("top");
+
+    assert_eq!(location.to_string(), "/search?q=rust&all#top");
+    assert_eq!(Location::parse(&location.to_string()), location);
+}
+
+#[test]
+fn encodes_and_decodes_query_and_fragment() {
+    let location = Location::new("/search")
+        .with_query("q", "rust & gpui")
+        .with_query("sort", "a=b")
+        .with_fragment("see also");
+
+    assert_eq!(
+        location.to_string(),
+        "/search?q=rust%20%26%20gpui&sort=a%3Db#see%20also"
+    );
+    assert_eq!(Location::parse(&location.to_string()), location);
+
+    let location = Location::parse("/search?q=hello+world");
+    assert_eq!(location.query_param("q"), Some("hello world"));
+}
+
+#[test]
+fn keeps_undecodable_query_values_as_written() {
+    let location = Location::parse("/search?q=100%&x=%FF#50%");
+
+    assert_eq!(location.path, "/search");
+    assert_eq!(location.query_param("q"), Some("100%"));
+    assert_eq!(location.query_param("x"), Some("%FF"));
+    assert_eq!(location.fragment.as_deref(), Some("50%"));
+}
@@ -0,0 +1,23 @@
+#![cfg(feature = "macros")]
+
+#[test]
+fn expands_to_route_table() {
+    let table = fs_router::routes!("tests/fixtures/pages");
+
+    assert_eq!(table.routes().len(), 3);
+    assert_eq!(table.fallbacks().len(), 1);
+    assert_eq!(*table.fallback_for("/missing").unwrap().1, "not found");
+
+    let (matched, handler) = table.resolve_handler("/users/5").unwrap();
+    assert_eq!(*handler, "user");
+    assert_eq!(matched.param("id"), Some("5"));
+    assert_eq!(matched.spec.source, "tests/fixtures/pages/users/[id].rs");
+
+    let (_, handler) = table.resolve_handler("/").unwrap();
+    assert_eq!(*handler, "index");
+
+    let (matched, _) = table.resolve_handler("/docs/a/b").unwrap();
+    let layouts = table.layouts_for(&matched.spec);
+    assert_eq!(layouts.len(), 1);
+    assert_eq!(layouts[0].1, "docs layout");
+}
@@ -0,0 +1,238 @@
+mod common;
+
+use common::page;
+use fs_router::core::matcher::RouteMatcher;
+use fs_router::core::{RouteSpec, RouteTable};
+
+fn table() -> RouteTable<&'static str> {
+    RouteTable::from_routes([
+        (page("pages/index.rs"), "index"),
+        (page("pages/users/[id].rs"), "user"),
+        (page("pages/users/new.rs"), "new_user"),
+        (page("pages/docs/[...slug].rs"), "docs"),
+        (page("pages/files/[...filepath]/info.rs"), "file_info"),
+    ])
+    .unwrap()
+}
+
+fn resolve(
+    table: &RouteTable<&'static str>,
+    path: &str,
+) -> Option<(&'static str, Vec<(String, String)>)> {
+    let matcher = RouteMatcher::new(table);
+    matcher
+        .resolve(path)
+        .map(|(index, params)| (table.routes()[index].1, params))
+}
+
+#[test]
+fn resolves_root() {
+    let table = table();
+    assert_eq!(resolve(&table, "/"), Some(("index", vec![])));
+}
+
+#[test]
+fn prefers_static_over_dynamic_segment() {
+    let table = table();
+    assert_eq!(resolve(&table, "/users/new"), Some(("new_user", vec![])));
+    assert_eq!(
+        resolve(&table, "/users/42"),
+        Some(("user", vec![("id".to_string(), "42".to_string())]))
+    );
+}
+
+#[test]
+fn resolves_catchall_routes() {
+    let table = table();
+    assert_eq!(
+        resolve(&table, "/docs/a/b/c"),
+        Some(("docs", vec![("slug".to_string(), "a/b/c".to_string())]))
+    );
+    assert_eq!(
+        resolve(&table, "/files/a/b/info"),
+        Some((
+            "file_info",
+            vec![("filepath".to_string(), "a/b".to_string())]
+        ))
+    );
+}
+
+#[test]
+fn returns_none_when_nothing_matches() {
+    let table = table();
+    assert_eq!(resolve(&table, "/users/42/edit"), None);
+    assert_eq!(resolve(&table, "/docs"), None);
+}
+
+#[test]
+fn ties_go_to_the_first_inserted_route() {
+    let table = RouteTable::from_routes([
+        (page("pages/[a]/b.rs"), "first"),
+        (page("pages/a/[b].rs"), "second"),
+    ])
+    .unwrap();
+
+    assert_eq!(
+        resolve(&table, "/a/b"),
+        Some(("first", vec![("a".to_string(), "a".to_string())]))
+    );
+}
+
+#[test]
+fn optional_catchall_matches_zero_or_more_segments() {
+    let table = RouteTable::from_routes([(page("pages/docs/[[...slug]].rs"), "docs")]).unwrap();
+
+    assert_eq!(resolve(&table, "/docs"), Some(("docs", vec![])));
+    assert_eq!(
+        resolve(&table, "/docs/a/b"),
+        Some(("docs", vec![("slug".to_string(), "a/b".to_string())]))
+    );
+    assert_eq!(resolve(&table, "/other"), None);
+}
+
+#[test]
+fn optional_catchall_ranks_below_required_routes() {
+    let table = RouteTable::from_routes([
+        (page("pages/docs/[[...slug]].rs"), "optional"),
+        (page("pages/docs/index.rs"), "index"),
+        (page("pages/docs/[...rest].rs"), "required"),
+    ])
+    .unwrap();
+
+    assert_eq!(resolve(&table, "/docs").unwrap().0, "index");
+    assert_eq!(resolve(&table, "/docs/a").unwrap().0, "required");
+    assert!(table.conflicts().is_empty());
+}
+
+#[test]
+fn optional_segment_binds_only_when_present() {
+    let table = RouteTable::from_routes([
+        (page("pages/pricing.rs"), "pricing"),
+        (page("pages/[[lang]]/pricing.rs"), "localized"),
+        (page("pages/[[lang]].rs"), "home"),
+        (page("pages/index.rs"), "index"),
+    ])
+    .unwrap();
+
+    assert_eq!(resolve(&table, "/pricing"), Some(("pricing", vec![])));
+    assert_eq!(
+        resolve(&table, "/en/pricing"),
+        Some(("localized", vec![("lang".to_string(), "en".to_string())]))
+    );
+    assert_eq!(resolve(&table, "/"), Some(("index", vec![])));
+    assert_eq!(
+        resolve(&table, "/en"),
+        Some(("home", vec![("lang".to_string(), "en".to_string())]))
+    );
+    assert_eq!(resolve(&table, "/en/fr/pricing"), None);
+}
+
+fn parsed(sources: &[&'static str]) -> RouteTable<&'static str> {
+    RouteTable::from_routes(sources.iter().map(|source| (page(source), *source))).unwrap()
+}
+
+#[test]
+fn constrained_params_fall_through_when_rejected() {
+    let table = parsed(&["pages/users/[id=integer].rs", "pages/users/[name].rs"]);
+
+    assert_eq!(
+        resolve(&table, "/users/42"),
+        Some((
+            "pages/users/[id=integer].rs",
+            vec![("id".to_string(), "42".to_string())]
+        ))
+    );
+    assert_eq!(
+        resolve(&table, "/users/abc"),
+        Some((
+            "pages/users/[name].rs",
+            vec![("name".to_string(), "abc".to_string())]
+        ))
+    );
+
+    let table = parsed(&["pages/posts/[id=uuid].rs"]);
+    assert!(resolve(&table, "/posts/67e55044-10b1-426f-9247-bb680e5fe0c8").is_some());
+    assert_eq!(resolve(&table, "/posts/42"), None);
+}
+
+#[test]
+fn catchall_matchers_check_every_segment() {
+    let table = parsed(&["pages/docs/[...path=slug].rs"]);
+
+    assert!(resolve(&table, "/docs/intro/getting-started").is_some());
+    assert_eq!(resolve(&table, "/docs/intro/Getting_Started"), None);
+}
+
+#[test]
+fn custom_matchers_are_registered_on_the_table() {
+    let mut table = parsed(&["pages/[lang=locale]/index.rs"]);
+    assert_eq!(resolve(&table, "/en"), None);
+
+    table.register_matcher("locale", |value| matches!(value, "en" | "fr"));
+    assert!(resolve(&table, "/en").is_some());
+    assert_eq!(resolve(&table, "/de"), None);
+}
+
+#[test]
+fn builtin_matchers() {
+    use fs_router::core::param_matcher::{is_integer, is_slug, is_uuid};
+
+    assert!(is_integer("0") && is_integer("-12"));
+    assert!(!is_integer("") && !is_integer("-") && !is_integer("1.5"));
+
+    assert!(is_uuid("67E55044-10b1-426f-9247-bb680e5fe0c8"));
+    assert!(!is_uuid("67e55044-10b1-426f-9247"));
+    assert!(!is_uuid("67e55044-10b1-426f-9247-bb680e5fe0cg"));
+
+    assert!(is_slug("hello-world-2"));
+    assert!(!is_slug("Hello") && !is_slug("a--b") && !is_slug("-a"));
+}
+
+#[test]
+fn mixed_segments_capture_every_param() {
+    let table = parsed(&[
+        "pages/posts/post-[id=integer].rs",
+        "pages/posts/post-new.rs",
+        "pages/posts/[slug].rs",
+        "pages/files/[name].[ext].rs",
+    ]);
+
+    assert_eq!(
+        resolve(&table, "/posts/post-42"),
+        Some((
+            "pages/posts/post-[id=integer].rs",
+            vec![("id".to_string(), "42".to_string())]
+        ))
+    );
+    assert_eq!(resolve(&table, "/posts/post-new").unwrap().0, "pages/posts/post-new.rs");
+    assert_eq!(resolve(&table, "/posts/post-abc").unwrap().0, "pages/posts/[slug].rs");
+    assert_eq!(
+        resolve(&table, "/files/archive.tar.gz"),
+        Some((
+            "pages/files/[name].[ext].rs",
+            vec![
+                ("name".to_string(), "archive.tar".to_string()),
+                ("ext".to_string(), "gz".to_string()),
+            ]
+        ))
+    );
+    assert_eq!(resolve(&table, "/files/readme"), None);
+}
+
+#[test]
+fn braces_without_declared_params_are_static() {
+    let table = RouteTable::from_routes([
+        (
+            RouteSpec {
+                path: "/files/{x}".to_string(),
+                ..page("pages/files/x.rs")
+            },
+            "literal",
+        ),
+        (page("pages/files/[name].rs"), "file"),
+    ])
+    .unwrap();
+
+    assert_eq!(resolve(&table, "/files/{x}").unwrap().0, "literal");
+    assert_eq!(resolve(&table, "/files/other").unwrap().0, "file");
+}
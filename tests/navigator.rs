@@ -0,0 +1,118 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fs_router::{GuardDecision, NavigateError, NavigationKind, Navigator, RouteTable};
+
+fn navigator() -> Navigator<&'static str> {
+    let table = RouteTable::from_routes(
+        [
+            "pages/index.rs",
+            "pages/login.rs",
+            "pages/users/[id].rs",
+            "pages/admin/index.rs",
+            "pages/404.rs",
+        ]
+        .map(|source| (fs_router::parse_file_path(source, None).unwrap(), source)),
+    )
+    .unwrap();
+
+    Navigator::new(table, "/")
+}
+
+/// Every event as `(from, to, kind)`, with `to` the matched pattern.
+type Seen = Rc<RefCell<Vec<(Option<String>, String, NavigationKind)>>>;
+
+fn record(navigator: &mut Navigator<&'static str>) -> Seen {
+    let seen = Seen::default();
+    let events = seen.clone();
+    navigator.subscribe(move |changed| {
+        events.borrow_mut().push((
+            changed.from.as_ref().map(|from| from.spec.path.clone()),
+            changed.to.spec.path.clone(),
+            changed.kind,
+        ));
+    });
+    seen
+}
+
+#[test]
+fn listeners_hear_every_navigation() {
+    let mut navigator = navigator();
+    let seen = record(&mut navigator);
+
+    navigator.navigate("/users/7").unwrap();
+    navigator.replace("/users/8").unwrap();
+    assert_eq!(navigator.forward(), Ok(false));
+    navigator.navigate("/missing").unwrap();
+    assert_eq!(navigator.back(), Ok(true));
+    assert_eq!(navigator.forward(), Ok(true));
+
+    let users = || Some("/users/:id".to_string());
+    assert_eq!(
+        *seen.borrow(),
+        vec![
+            (Some("/".to_string()), "/users/:id".to_string(), NavigationKind::Push),
+            (users(), "/users/:id".to_string(), NavigationKind::Replace),
+            (users(), "/*".to_string(), NavigationKind::Push),
+            (Some("/*".to_string()), "/users/:id".to_string(), NavigationKind::Back),
+            (users(), "/*".to_string(), NavigationKind::Forward),
+        ]
+    );
+
+    let (matched, handler) = navigator.current().unwrap();
+    assert_eq!(matched.spec.path, "/*");
+    assert_eq!(*handler, "pages/404.rs");
+}
+
+#[test]
+fn guards_run_before_listeners() {
+    let mut navigator = navigator();
+    navigator.before_navigate_at("/admin", |_, _| GuardDecision::Redirect("/login".to_string()));
+    navigator.before_navigate_at("/users", |_, to| {
+        if to.path == "/users/0" {
+            GuardDecision::Cancel
+        } else {
+            GuardDecision::Allow
+        }
+    });
+    let seen = record(&mut navigator);
+
+    navigator.navigate("/admin").unwrap();
+    assert_eq!(navigator.route(), "/login");
+    assert_eq!(navigator.history().entries(), ["/", "/login"]);
+
+    assert_eq!(navigator.navigate("/users/0"), Err(NavigateError::Cancelled));
+    assert_eq!(navigator.route(), "/login");
+    assert_eq!(seen.borrow().len(), 1);
+    assert_eq!(seen.borrow()[0].1, "/login");
+}
+
+#[test]
+fn unsubscribed_listeners_are_not_called() {
+    let mut navigator = navigator();
+    let calls = Rc::new(RefCell::new(0));
+    let counter = calls.clone();
+    let id = navigator.subscribe(move |_| *counter.borrow_mut() += 1);
+
+    navigator.navigate("/login").unwrap();
+    assert!(navigator.unsubscribe(id));
+    navigator.navigate("/").unwrap();
+
+    assert_eq!(*calls.borrow(), 1);
+    assert!(!navigator.unsubscribe(id));
+}
+
+#[test]
+fn undecodable_paths_land_on_the_fallback() {
+    let mut navigator = navigator();
+    let seen = record(&mut navigator);
+
+    navigator.navigate("/users/%zz").unwrap();
+    navigator.navigate("/users/%FF").unwrap();
+
+    let (matched, handler) = navigator.current().unwrap();
+    assert_eq!(*handler, "pages/404.rs");
+    assert_eq!(matched.spec.path, "/*");
+    assert_eq!(seen.borrow().len(), 2);
+    assert!(seen.borrow().iter().all(|(_, to, _)| to == "/*"));
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "serde")]
+
+use fs_router::{ParamsError, RouteTable};
+use serde::Deserialize;
+
+fn table() -> RouteTable<&'static str> {
+    RouteTable::from_routes(
+        [
+            "pages/users/[id]/[tab].rs",
+            "pages/docs/[...slug].rs",
+            "pages/pages/[[...path]].rs",
+            "pages/[[lang]]/pricing.rs",
+        ]
+        .map(|source| (fs_router::parse_file_path(source, None).unwrap(), source)),
+    )
+    .unwrap()
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Tab {
+    Posts,
+    Settings,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct UserParams {
+    id: u32,
+    tab: Tab,
+}
+
+#[test]
+fn converts_numbers_and_enums() {
+    let matched = table().resolve("/users/42/settings").unwrap();
+
+    assert_eq!(
+        matched.params_as::<UserParams>().unwrap(),
+        UserParams {
+            id: 42,
+            tab: Tab::Settings
+        }
+    );
+}
+
+#[test]
+fn splits_catchall_into_sequences() {
+    #[derive(Deserialize)]
+    struct Docs {
+        slug: Vec<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct Pages {
+        #[serde(default)]
+        path: Vec<String>,
+    }
+
+    let table = table();
+
+    let docs: Docs = table.resolve("/docs/guide/intro").unwrap().params_as().unwrap();
+    assert_eq!(docs.slug, ["guide", "intro"]);
+
+    let pages: Pages = table.resolve("/pages").unwrap().params_as().unwrap();
+    assert!(pages.path.is_empty());
+}
+
+#[test]
+fn missing_optional_params_are_none() {
+    #[derive(Deserialize)]
+    struct Pricing {
+        lang: Option<String>,
+    }
+
+    let table = table();
+
+    let pricing: Pricing = table.resolve("/pricing").unwrap().params_as().unwrap();
+    assert_eq!(pricing.lang, None);
+
+    let pricing: Pricing = table.resolve("/en/pricing").unwrap().params_as().unwrap();
+    assert_eq!(pricing.lang.as_deref(), Some("en"));
+}
+
+#[test]
+fn errors_name_the_param() {
+    let table = table();
+
+    let err = table
+        .resolve("/users/abc/posts")
+        .unwrap()
+        .params_as::<UserParams>()
+        .unwrap_err();
+    assert_eq!(err.param.as_deref(), Some("id"));
+    assert_eq!(err.to_string(), "invalid param id : expected u32, found \"abc\"");
+
+    let err = table
+        .resolve("/users/1/friends")
+        .unwrap()
+        .params_as::<UserParams>()
+        .unwrap_err();
+    assert_eq!(err.param.as_deref(), Some("tab"));
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Missing {
+        id: u32,
+        page: u32,
+    }
+
+    let err: ParamsError = table
+        .resolve("/users/1/posts")
+        .unwrap()
+        .params_as::<Missing>()
+        .unwrap_err();
+    assert_eq!(err.param.as_deref(), Some("page"));
+}
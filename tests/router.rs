@@ -0,0 +1,164 @@
+mod common;
+
+use common::page;
+use fs_router::core::router::score_spec;
+use fs_router::{NavigateError, RouteTable};
+
+fn table() -> RouteTable<&'static str> {
+    RouteTable::from_routes([
+        (page("pages/index.rs"), "index"),
+        (page("pages/users/[id].rs"), "user"),
+        (page("pages/users/new.rs"), "new_user"),
+    ])
+    .unwrap()
+}
+
+#[test]
+fn resolve_returns_best_match_with_params() {
+    let table = table();
+
+    let matched = table.resolve("/users/7").unwrap();
+    assert_eq!(matched.spec.path, "/users/:id");
+    assert_eq!(matched.param("id"), Some("7"));
+
+    let matched = table.resolve("/users/new").unwrap();
+    assert_eq!(matched.spec.path, "/users/new");
+    assert!(table.resolve("/missing").is_none());
+}
+
+#[test]
+fn resolve_handler_returns_the_stored_handler() {
+    let table = table();
+    let (matched, handler) = table.resolve_handler("/").unwrap();
+    assert_eq!(matched.spec.path, "/");
+    assert_eq!(*handler, "index");
+}
+
+#[test]
+fn insert_rebuilds_the_compiled_matcher() {
+    let mut table = table();
+    assert!(table.resolve("/about").is_none());
+
+    table.insert(page("pages/about.rs"), "about").unwrap();
+    assert_eq!(*table.resolve_handler("/about").unwrap().1, "about");
+}
+
+#[test]
+fn path_for_builds_paths_by_id() {
+    let table = table();
+
+    let user = page("pages/users/[id].rs").id;
+    let index = page("pages/index.rs").id;
+
+    assert_eq!(table.path_for(user, &[("id", "9")]).unwrap(), "/users/9");
+    assert_eq!(table.path_for(index, &[]).unwrap(), "/");
+    assert_eq!(
+        table.path_for(99, &[]).unwrap_err(),
+        NavigateError::RouteIdNotFound(99)
+    );
+}
+
+#[test]
+fn static_segments_score_higher_than_params() {
+    assert!(
+        score_spec(&page("pages/users/new.rs")) > score_spec(&page("pages/users/[id].rs"))
+    );
+}
+
+#[test]
+fn build_path_omits_empty_optional_catchall() {
+    let spec = page("pages/docs/[[...slug]].rs");
+
+    assert_eq!(fs_router::build_path(&spec, &[]).unwrap(), "/docs");
+    assert_eq!(fs_router::build_path(&spec, &[("slug", "")]).unwrap(), "/docs");
+    assert_eq!(
+        fs_router::build_path(&spec, &[("slug", "a/b")]).unwrap(),
+        "/docs/a/b"
+    );
+}
+
+#[test]
+fn build_path_omits_missing_optional_segment() {
+    let spec = page("pages/[[lang]]/pricing.rs");
+
+    assert_eq!(fs_router::build_path(&spec, &[]).unwrap(), "/pricing");
+    assert_eq!(
+        fs_router::build_path(&spec, &[("lang", "en")]).unwrap(),
+        "/en/pricing"
+    );
+}
+
+#[test]
+fn build_path_with_checks_matchers() {
+    let matchers = fs_router::core::param_matcher::ParamMatchers::new();
+
+    let spec = page("pages/users/[id=integer].rs");
+    assert_eq!(
+        fs_router::build_path_with(&spec, &[("id", "42")], &matchers).unwrap(),
+        "/users/42"
+    );
+    assert_eq!(
+        fs_router::build_path_with(&spec, &[("id", "abc")], &matchers),
+        Err(NavigateError::InvalidParam {
+            name: "id".to_string(),
+            matcher: "integer".to_string(),
+        })
+    );
+
+    let spec = page("pages/docs/[...path=slug].rs");
+    assert!(fs_router::build_path_with(&spec, &[("path", "intro/setup")], &matchers).is_ok());
+    assert!(fs_router::build_path_with(&spec, &[("path", "intro/Setup")], &matchers).is_err());
+
+    let spec = page("pages/[[lang=locale]]/about.rs");
+    assert_eq!(
+        fs_router::build_path_with(&spec, &[], &matchers).unwrap(),
+        "/about"
+    );
+    assert!(fs_router::build_path_with(&spec, &[("lang", "en")], &matchers).is_err());
+}
+
+#[test]
+fn build_path_fills_mixed_segments() {
+    let spec = page("pages/files/[name].[ext].rs");
+
+    assert_eq!(
+        fs_router::build_path(&spec, &[("name", "report"), ("ext", "pdf")]).unwrap(),
+        "/files/report.pdf"
+    );
+    assert_eq!(
+        fs_router::build_path(&spec, &[("name", "report")]).unwrap_err(),
+        NavigateError::MissingParam {
+            name: "ext".to_string()
+        }
+    );
+}
+
+#[test]
+fn resolve_matches_only_the_path() {
+    let table = table();
+
+    let matched = table.resolve("/users/7?tab=posts&sort=new#latest").unwrap();
+    assert_eq!(matched.spec.path, "/users/:id");
+    assert_eq!(matched.param("id"), Some("7"));
+    assert_eq!(matched.query_param("tab"), Some("posts"));
+    assert_eq!(matched.location.path, "/users/7");
+    assert_eq!(matched.location.query.len(), 2);
+    assert_eq!(matched.location.fragment.as_deref(), Some("latest"));
+
+    let matched = table.resolve("/?q=x").unwrap();
+    assert_eq!(matched.spec.path, "/");
+
+    let matched = fs_router::match_route(&page("pages/search.rs"), "/search?q=rust").unwrap();
+    assert_eq!(matched.query_param("q"), Some("rust"));
+}
+
+#[test]
+fn build_url_appends_query_params() {
+    let spec = page("pages/users/[id].rs");
+
+    assert_eq!(
+        fs_router::build_url(&spec, &[("id", "7")], &[("tab", "posts"), ("page", "2")]).unwrap(),
+        "/users/7?tab=posts&page=2"
+    );
+    assert_eq!(fs_router::build_url(&spec, &[("id", "7")], &[]).unwrap(), "/users/7");
+}
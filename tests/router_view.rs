@@ -0,0 +1,853 @@
+#![cfg(feature = "gpui")]
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use fs_router::adapters::gpui::{
+    Back, Forward, Outlet, RouteHandler, bind_history_keys, on_leave, page,
+};
+use fs_router::{
+    GuardDecision, LeaveDecision, NavigateError, NavigationKind, RouteChanged, RouteContext,
+    RouteTable, RouterHandle, RouterView,
+};
+use gpui::{
+    AnyWindowHandle, Context, Entity, EntityId, IntoElement, Modifiers, MouseButton,
+    NavigationDirection, Render, TestAppContext, VisualTestContext, Window, div, point, prelude::*,
+    px,
+};
+
+struct Blank;
+
+impl Render for Blank {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+    }
+}
+
+/// Remembers the router and route its own context resolves to.
+#[derive(Clone, Default)]
+struct Probe {
+    router: Rc<RefCell<Option<RouterHandle>>>,
+    route: Rc<RefCell<Option<String>>>,
+}
+
+impl Probe {
+    fn page(&self) -> RouteHandler {
+        let probe = self.clone();
+        page(move |_, _, cx| {
+            let probe = probe.clone();
+            cx.new(|_| probe).into()
+        })
+    }
+
+    fn route(&self) -> Option<String> {
+        self.route.borrow().clone()
+    }
+}
+
+impl Render for Probe {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        *self.router.borrow_mut() = Some(cx.router());
+        *self.route.borrow_mut() = cx.current_route().map(|matched| matched.spec.path.clone());
+        div()
+    }
+}
+
+struct Layout {
+    outlet: Entity<Outlet>,
+}
+
+impl Render for Layout {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div().child(self.outlet.clone())
+    }
+}
+
+/// A layout that records the outlet of every instance it builds.
+fn layout(outlets: &Rc<RefCell<Vec<Entity<Outlet>>>>) -> RouteHandler {
+    let outlets = outlets.clone();
+    page(move |_, _, cx| {
+        let outlet = cx.outlet().unwrap();
+        outlets.borrow_mut().push(outlet.clone());
+        cx.new(|_| Layout { outlet }).into()
+    })
+}
+
+/// A page that records the id of every view it builds.
+fn recorded(views: &Rc<RefCell<Vec<EntityId>>>) -> RouteHandler {
+    let views = views.clone();
+    page(move |_, _, cx| {
+        let view: gpui::AnyView = cx.new(|_| Blank).into();
+        views.borrow_mut().push(view.entity_id());
+        view
+    })
+}
+
+fn blank() -> RouteHandler {
+    page(|_, _, cx| cx.new(|_| Blank).into())
+}
+
+fn table(pages: &[(&str, RouteHandler)]) -> RouteTable<RouteHandler> {
+    RouteTable::from_routes(pages.iter().map(|(file, handler)| {
+        let source = format!("pages/{file}");
+        (fs_router::parse_file_path(&source, None).unwrap(), handler.clone())
+    }))
+    .unwrap()
+}
+
+fn open(cx: &mut TestAppContext, router: RouterView) -> (Entity<RouterView>, AnyWindowHandle) {
+    let window = cx.add_window(|_, _| router);
+    cx.run_until_parked();
+    (window.root(cx).unwrap(), window.into())
+}
+
+fn route(router: &Entity<RouterView>, cx: &TestAppContext) -> String {
+    cx.read(|cx| router.read(cx).route().to_string())
+}
+
+#[gpui::test]
+fn handles_from_factories_navigate_their_router(cx: &mut TestAppContext) {
+    let built = Rc::new(RefCell::new(None));
+    let index = page({
+        let built = built.clone();
+        move |_, _, cx| {
+            *built.borrow_mut() = Some(cx.router());
+            cx.new(|_| Blank).into()
+        }
+    });
+
+    let (router, _) = open(
+        cx,
+        RouterView::new(table(&[("index.rs", index), ("about.rs", blank())]), "/"),
+    );
+
+    let handle = built.borrow().clone().unwrap();
+    assert_eq!(handle.entity(), Some(router.clone()));
+
+    cx.update(|cx| handle.navigate("/about", cx)).unwrap();
+    assert_eq!(route(&router, cx), "/about");
+
+    assert_eq!(cx.update(|cx| handle.back(cx)), Ok(true));
+    assert_eq!(route(&router, cx), "/");
+}
+
+#[gpui::test]
+fn pages_resolve_the_router_that_built_them(cx: &mut TestAppContext) {
+    let probe = Probe::default();
+
+    let pages = [("index.rs", blank()), ("about.rs", blank())];
+    let (main, _) = open(cx, RouterView::new(table(&pages), "/").with_name("main"));
+    let (sidebar, _) = open(
+        cx,
+        RouterView::new(table(&[("index.rs", probe.page()), ("about.rs", blank())]), "/")
+            .with_name("sidebar"),
+    );
+
+    let handle = probe.router.borrow().clone().unwrap();
+    assert_eq!(handle.entity(), Some(sidebar.clone()));
+
+    assert_eq!(
+        cx.update(|cx| cx.router().navigate("/about", cx)),
+        Err(NavigateError::AmbiguousRouter)
+    );
+
+    cx.update(|cx| cx.router_named("main").navigate("/about", cx)).unwrap();
+    assert_eq!(route(&main, cx), "/about");
+    assert_eq!(route(&sidebar, cx), "/");
+
+    assert_eq!(
+        cx.update(|cx| cx.router_named("missing").navigate("/about", cx)),
+        Err(NavigateError::NoRouter)
+    );
+}
+
+/// A page that creates a `Probe` the first time it renders, as a component
+/// nested inside a page would.
+struct Nested {
+    probe: Probe,
+    child: Rc<RefCell<Option<Entity<Probe>>>>,
+}
+
+impl Render for Nested {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let child = self
+            .child
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                let probe = self.probe.clone();
+                cx.new(|_| probe)
+            })
+            .clone();
+        div().child(div().child(child))
+    }
+}
+
+#[gpui::test]
+fn nested_views_resolve_the_router_they_render_in(cx: &mut TestAppContext) {
+    let probe = Probe::default();
+    let child = Rc::new(RefCell::new(None));
+    let nested = page({
+        let probe = probe.clone();
+        let child = child.clone();
+        move |_, _, cx| {
+            let (probe, child) = (probe.clone(), child.clone());
+            cx.new(|_| Nested { probe, child }).into()
+        }
+    });
+
+    let pages = [("index.rs", blank()), ("about.rs", blank())];
+    let (sidebar, _) = open(cx, RouterView::new(table(&pages), "/").with_name("sidebar"));
+    let (main, _) = open(
+        cx,
+        RouterView::new(table(&[("index.rs", nested), ("about.rs", blank())]), "/"),
+    );
+
+    let rendered = probe.router.borrow().clone().unwrap();
+    assert_eq!(rendered.entity(), Some(main.clone()));
+    assert_eq!(probe.route().as_deref(), Some("/"));
+
+    // Outside of any render, the nested view still finds its router.
+    let child = child.borrow().clone().unwrap();
+    let handle = child.update(cx, |_, cx| cx.router());
+    assert_eq!(handle.entity(), Some(main.clone()));
+    assert_eq!(
+        cx.update(|cx| cx.router().navigate("/about", cx)),
+        Err(NavigateError::AmbiguousRouter)
+    );
+
+    cx.update(|cx| handle.navigate("/about", cx)).unwrap();
+    assert_eq!(route(&main, cx), "/about");
+    assert_eq!(route(&sidebar, cx), "/");
+}
+
+#[gpui::test]
+fn handles_fail_once_their_router_is_dropped(cx: &mut TestAppContext) {
+    let (router, window) = open(cx, RouterView::new(table(&[("index.rs", blank())]), "/"));
+
+    let handle = cx.update(|cx| cx.router());
+    assert_eq!(handle.entity(), Some(router.clone()));
+    drop(router);
+
+    cx.update_window(window, |_, window, _| window.remove_window())
+        .unwrap();
+    cx.run_until_parked();
+
+    assert_eq!(handle.entity(), None);
+    assert_eq!(
+        cx.update(|cx| handle.navigate("/", cx)),
+        Err(NavigateError::NoRouter)
+    );
+    assert_eq!(
+        cx.update(|cx| cx.router().navigate("/", cx)),
+        Err(NavigateError::NoRouter)
+    );
+}
+
+#[gpui::test]
+fn each_router_has_its_own_current_route(cx: &mut TestAppContext) {
+    let built = Rc::new(RefCell::new(None));
+    let user = page({
+        let built = built.clone();
+        move |_, _, cx| {
+            *built.borrow_mut() = cx.route_param("id").map(str::to_string);
+            cx.new(|_| Blank).into()
+        }
+    });
+    let main_probe = Probe::default();
+    let side_probe = Probe::default();
+
+    let (main, _) = open(
+        cx,
+        RouterView::new(
+            table(&[("home.rs", main_probe.page()), ("users/[id].rs", user)]),
+            "/users/7",
+        )
+        .with_name("main"),
+    );
+    assert_eq!(built.borrow().as_deref(), Some("7"));
+    assert_eq!(
+        cx.update(|cx| cx.current_route().map(|matched| matched.spec.path.clone())),
+        Some("/users/:id".to_string())
+    );
+
+    let (_, _) = open(
+        cx,
+        RouterView::new(table(&[("index.rs", side_probe.page())]), "/").with_name("sidebar"),
+    );
+    assert_eq!(side_probe.route().as_deref(), Some("/"));
+    assert!(cx.update(|cx| cx.current_route().is_none()));
+
+    cx.update(|cx| cx.router_named("main").navigate("/home", cx)).unwrap();
+    assert_eq!(
+        cx.read(|cx| main.read(cx).current_match().map(|matched| matched.spec.path.clone())),
+        Some("/home".to_string())
+    );
+
+    cx.run_until_parked();
+    assert_eq!(main_probe.route().as_deref(), Some("/home"));
+    assert_eq!(side_probe.route().as_deref(), Some("/"));
+}
+
+#[gpui::test]
+fn current_route_follows_navigation_before_rendering(cx: &mut TestAppContext) {
+    let (router, _) = open(
+        cx,
+        RouterView::new(table(&[("index.rs", blank()), ("users/[id].rs", blank())]), "/"),
+    );
+
+    router
+        .update(cx, |router, cx| router.navigate("/users/3", cx))
+        .unwrap();
+
+    assert_eq!(
+        cx.update(|cx| cx.route_param("id").map(str::to_string)),
+        Some("3".to_string())
+    );
+}
+
+#[gpui::test]
+fn layouts_stay_mounted_while_navigating_inside_them(cx: &mut TestAppContext) {
+    let outlets = Rc::new(RefCell::new(Vec::new()));
+    let views = Rc::new(RefCell::new(Vec::new()));
+
+    let (router, _) = open(
+        cx,
+        RouterView::new(
+            table(&[
+                ("settings/_layout.rs", layout(&outlets)),
+                ("settings/profile.rs", recorded(&views)),
+                ("settings/billing.rs", recorded(&views)),
+                ("about.rs", blank()),
+            ]),
+            "/settings/profile",
+        ),
+    );
+
+    let navigate = |cx: &mut TestAppContext, route: &str| {
+        router
+            .update(cx, |router, cx| router.navigate(route.to_string(), cx))
+            .unwrap();
+        cx.run_until_parked();
+    };
+    let child = |cx: &mut TestAppContext, outlet: &Entity<Outlet>| {
+        cx.read(|cx| outlet.read(cx).child().map(|child| child.entity_id()))
+    };
+
+    assert_eq!(outlets.borrow().len(), 1);
+    let outlet = outlets.borrow()[0].clone();
+    assert_eq!(child(cx, &outlet), Some(views.borrow()[0]));
+
+    navigate(cx, "/settings/billing");
+    assert_eq!(outlets.borrow().len(), 1);
+    assert_eq!(views.borrow().len(), 2);
+    assert_eq!(child(cx, &outlet), Some(views.borrow()[1]));
+
+    navigate(cx, "/about");
+    navigate(cx, "/settings/profile");
+    assert_eq!(outlets.borrow().len(), 2);
+    let remounted = outlets.borrow()[1].clone();
+    assert_ne!(remounted.entity_id(), outlet.entity_id());
+    assert_eq!(child(cx, &remounted), Some(views.borrow()[2]));
+}
+
+#[gpui::test]
+fn layouts_remount_when_their_params_change(cx: &mut TestAppContext) {
+    let teams = Rc::new(RefCell::new(Vec::new()));
+    let settings = Rc::new(RefCell::new(Vec::new()));
+    let views = Rc::new(RefCell::new(Vec::new()));
+
+    let (router, _) = open(
+        cx,
+        RouterView::new(
+            table(&[
+                ("teams/[team]/_layout.rs", layout(&teams)),
+                ("teams/[team]/settings/_layout.rs", layout(&settings)),
+                ("teams/[team]/settings/index.rs", recorded(&views)),
+                ("teams/[team]/index.rs", recorded(&views)),
+            ]),
+            "/teams/a/settings",
+        ),
+    );
+
+    let navigate = |cx: &mut TestAppContext, route: &str| {
+        router
+            .update(cx, |router, cx| router.navigate(route.to_string(), cx))
+            .unwrap();
+        cx.run_until_parked();
+    };
+
+    assert_eq!((teams.borrow().len(), settings.borrow().len()), (1, 1));
+
+    // Leaving the inner layout keeps the outer one and refills its outlet.
+    navigate(cx, "/teams/a");
+    assert_eq!((teams.borrow().len(), settings.borrow().len()), (1, 1));
+    let outlet = teams.borrow()[0].clone();
+    assert_eq!(
+        cx.read(|cx| outlet.read(cx).child().map(|child| child.entity_id())),
+        Some(views.borrow()[1])
+    );
+
+    navigate(cx, "/teams/b");
+    assert_eq!((teams.borrow().len(), settings.borrow().len()), (2, 1));
+}
+
+/// The answer a guarded page or layout gives its leave hook, and the
+/// locations it was asked about.
+#[derive(Clone)]
+struct Leave {
+    decision: Rc<Cell<LeaveDecision>>,
+    asked: Rc<RefCell<Vec<String>>>,
+}
+
+impl Leave {
+    fn new() -> Self {
+        Self {
+            decision: Rc::new(Cell::new(LeaveDecision::Leave)),
+            asked: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    fn page(&self, inner: RouteHandler) -> RouteHandler {
+        let leave = self.clone();
+        page(move |matched, window, cx| {
+            let hook = leave.clone();
+            on_leave(cx, move |to, _| {
+                hook.asked.borrow_mut().push(to.to_string());
+                hook.decision.get()
+            });
+            inner(matched, window, cx)
+        })
+    }
+
+    fn set(&self, decision: LeaveDecision) {
+        self.decision.set(decision);
+    }
+
+    fn asked(&self) -> Vec<String> {
+        self.asked.borrow().clone()
+    }
+}
+
+fn history(router: &Entity<RouterView>, cx: &TestAppContext) -> (Vec<String>, usize) {
+    cx.read(|cx| {
+        let history = router.read(cx).history();
+        (history.entries().to_vec(), history.index())
+    })
+}
+
+fn guarded(cx: &mut TestAppContext, leave: &Leave) -> Entity<RouterView> {
+    let pages = [
+        ("index.rs", blank()),
+        ("edit.rs", leave.page(blank())),
+        ("about.rs", blank()),
+    ];
+    let (router, _) = open(cx, RouterView::new(table(&pages), "/"));
+
+    router
+        .update(cx, |router, cx| router.navigate("/edit", cx))
+        .unwrap();
+    cx.run_until_parked();
+    router
+}
+
+#[gpui::test]
+fn staying_cancels_and_keeps_the_history(cx: &mut TestAppContext) {
+    let leave = Leave::new();
+    let router = guarded(cx, &leave);
+    let before = history(&router, cx);
+
+    leave.set(LeaveDecision::Stay);
+    assert_eq!(
+        router.update(cx, |router, cx| router.navigate("/about?tab=1", cx)),
+        Err(NavigateError::Cancelled)
+    );
+    assert_eq!(leave.asked(), vec!["/about?tab=1"]);
+    assert_eq!(route(&router, cx), "/edit");
+    assert_eq!(history(&router, cx), before);
+    assert!(cx.read(|cx| router.read(cx).pending_navigation().is_none()));
+
+    // Navigating to the route already shown does not ask.
+    router
+        .update(cx, |router, cx| router.replace("/edit", cx))
+        .unwrap();
+    assert_eq!(leave.asked().len(), 1);
+}
+
+#[gpui::test]
+fn deferred_navigations_wait_for_an_answer(cx: &mut TestAppContext) {
+    let leave = Leave::new();
+    let router = guarded(cx, &leave);
+    let before = history(&router, cx);
+
+    leave.set(LeaveDecision::Defer);
+    assert_eq!(
+        router.update(cx, |router, cx| router.navigate("/about", cx)),
+        Err(NavigateError::Deferred)
+    );
+    assert_eq!(
+        cx.read(|cx| router.read(cx).pending_navigation().map(str::to_string)),
+        Some("/about".to_string())
+    );
+    assert_eq!(route(&router, cx), "/edit");
+
+    assert!(router.update(cx, |router, _| router.cancel_leave()));
+    assert!(!router.update(cx, |router, _| router.cancel_leave()));
+    assert_eq!(route(&router, cx), "/edit");
+    assert_eq!(history(&router, cx), before);
+    assert_eq!(router.update(cx, |router, cx| router.confirm_leave(cx)), Ok(false));
+
+    assert_eq!(
+        router.update(cx, |router, cx| router.navigate("/about", cx)),
+        Err(NavigateError::Deferred)
+    );
+    assert_eq!(router.update(cx, |router, cx| router.confirm_leave(cx)), Ok(true));
+    assert_eq!(route(&router, cx), "/about");
+    assert_eq!(leave.asked().len(), 2);
+    assert_eq!(
+        history(&router, cx),
+        (vec!["/".to_string(), "/edit".to_string(), "/about".to_string()], 2)
+    );
+}
+
+#[gpui::test]
+fn history_moves_ask_the_page_first(cx: &mut TestAppContext) {
+    let leave = Leave::new();
+    let router = guarded(cx, &leave);
+    let before = history(&router, cx);
+
+    leave.set(LeaveDecision::Stay);
+    assert_eq!(
+        router.update(cx, |router, cx| router.back(cx)),
+        Err(NavigateError::Cancelled)
+    );
+    assert_eq!(history(&router, cx), before);
+
+    leave.set(LeaveDecision::Defer);
+    assert_eq!(
+        router.update(cx, |router, cx| router.go(-1, cx)),
+        Err(NavigateError::Deferred)
+    );
+    assert_eq!(history(&router, cx), before);
+    assert_eq!(leave.asked(), vec!["/", "/"]);
+
+    assert_eq!(router.update(cx, |router, cx| router.confirm_leave(cx)), Ok(true));
+    assert_eq!(route(&router, cx), "/");
+    assert_eq!(
+        history(&router, cx),
+        (vec!["/".to_string(), "/edit".to_string()], 0)
+    );
+    assert!(cx.read(|cx| router.read(cx).can_go_forward()));
+
+    // Pages without hooks are left freely.
+    cx.run_until_parked();
+    assert_eq!(router.update(cx, |router, cx| router.forward(cx)), Ok(true));
+    assert_eq!(route(&router, cx), "/edit");
+}
+
+#[gpui::test]
+fn layout_hooks_are_asked_when_leaving_the_layout(cx: &mut TestAppContext) {
+    let leave = Leave::new();
+    let outlets = Rc::new(RefCell::new(Vec::new()));
+    let pages = [
+        ("settings/_layout.rs", leave.page(layout(&outlets))),
+        ("settings/profile.rs", blank()),
+        ("settings/billing.rs", blank()),
+        ("about.rs", blank()),
+    ];
+    let (router, _) = open(cx, RouterView::new(table(&pages), "/settings/profile"));
+    leave.set(LeaveDecision::Stay);
+
+    router
+        .update(cx, |router, cx| router.navigate("/settings/billing", cx))
+        .unwrap();
+    cx.run_until_parked();
+    assert!(leave.asked().is_empty());
+
+    assert_eq!(
+        router.update(cx, |router, cx| router.navigate("/about", cx)),
+        Err(NavigateError::Cancelled)
+    );
+    assert_eq!(leave.asked(), vec!["/about"]);
+    assert_eq!(route(&router, cx), "/settings/billing");
+}
+
+/// A guard on `/admin` that answers with whatever `decision` holds and
+/// records every url it was asked about.
+#[derive(Clone)]
+struct AdminGuard {
+    decision: Rc<RefCell<GuardDecision>>,
+    asked: Rc<RefCell<Vec<String>>>,
+}
+
+impl AdminGuard {
+    fn new() -> Self {
+        Self {
+            decision: Rc::new(RefCell::new(GuardDecision::Allow)),
+            asked: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    fn open(&self, cx: &mut TestAppContext) -> (Entity<RouterView>, u64) {
+        let pages = [
+            ("index.rs", blank()),
+            ("about.rs", blank()),
+            ("admin.rs", blank()),
+            ("login.rs", blank()),
+        ];
+        let mut router = RouterView::new(table(&pages), "/");
+        let admin = router
+            .table()
+            .routes()
+            .iter()
+            .find(|(spec, _)| spec.path == "/admin")
+            .map(|(spec, _)| spec.id)
+            .unwrap();
+
+        let guard = self.clone();
+        router.before_navigate_at("/admin", move |_, to| {
+            guard.asked.borrow_mut().push(to.to_string());
+            guard.decision.borrow().clone()
+        });
+
+        (open(cx, router).0, admin)
+    }
+
+    fn set(&self, decision: GuardDecision) {
+        *self.decision.borrow_mut() = decision;
+    }
+
+    fn asked(&self) -> Vec<String> {
+        self.asked.borrow().clone()
+    }
+}
+
+fn entries(routes: &[&str], index: usize) -> (Vec<String>, usize) {
+    (routes.iter().map(|route| route.to_string()).collect(), index)
+}
+
+#[gpui::test]
+fn guards_run_for_every_entry_point(cx: &mut TestAppContext) {
+    let guard = AdminGuard::new();
+    let (router, admin) = guard.open(cx);
+
+    router
+        .update(cx, |router, cx| router.navigate("/admin", cx))
+        .unwrap();
+    assert_eq!(route(&router, cx), "/admin");
+    router
+        .update(cx, |router, cx| router.navigate("/about", cx))
+        .unwrap();
+
+    assert_eq!(router.update(cx, |router, cx| router.back(cx)), Ok(true));
+    assert_eq!(route(&router, cx), "/admin");
+    assert_eq!(router.update(cx, |router, cx| router.back(cx)), Ok(true));
+    assert_eq!(router.update(cx, |router, cx| router.forward(cx)), Ok(true));
+    assert_eq!(route(&router, cx), "/admin");
+
+    router
+        .update(cx, |router, cx| router.replace("/admin?tab=users", cx))
+        .unwrap();
+    router
+        .update(cx, |router, cx| router.navigate_by_id(admin, &[], cx))
+        .unwrap();
+    assert_eq!(route(&router, cx), "/admin");
+
+    assert_eq!(
+        guard.asked(),
+        vec!["/admin", "/admin", "/admin", "/admin?tab=users", "/admin"]
+    );
+}
+
+#[gpui::test]
+fn cancelling_guards_leave_the_router_as_it_was(cx: &mut TestAppContext) {
+    let guard = AdminGuard::new();
+    let (router, admin) = guard.open(cx);
+    for path in ["/admin", "/about"] {
+        router
+            .update(cx, |router, cx| router.navigate(path, cx))
+            .unwrap();
+    }
+    let before = history(&router, cx);
+
+    guard.set(GuardDecision::Cancel);
+    assert_eq!(
+        router.update(cx, |router, cx| router.navigate("/admin", cx)),
+        Err(NavigateError::Cancelled)
+    );
+    assert_eq!(
+        router.update(cx, |router, cx| router.replace("/admin", cx)),
+        Err(NavigateError::Cancelled)
+    );
+    assert_eq!(
+        router.update(cx, |router, cx| router.navigate_by_id(admin, &[], cx)),
+        Err(NavigateError::Cancelled)
+    );
+    assert_eq!(
+        router.update(cx, |router, cx| router.back(cx)),
+        Err(NavigateError::Cancelled)
+    );
+    assert_eq!(route(&router, cx), "/about");
+    assert_eq!(history(&router, cx), before);
+
+    guard.set(GuardDecision::Allow);
+    router.update(cx, |router, cx| router.go(-2, cx)).unwrap();
+    guard.set(GuardDecision::Cancel);
+    assert_eq!(
+        router.update(cx, |router, cx| router.forward(cx)),
+        Err(NavigateError::Cancelled)
+    );
+    assert_eq!(route(&router, cx), "/");
+    assert_eq!(history(&router, cx), entries(&["/", "/admin", "/about"], 0));
+}
+
+#[gpui::test]
+fn guard_redirects_replace_the_entry_moved_to(cx: &mut TestAppContext) {
+    let guard = AdminGuard::new();
+    let (router, admin) = guard.open(cx);
+
+    guard.set(GuardDecision::Redirect("/login".to_string()));
+    router
+        .update(cx, |router, cx| router.navigate("/admin", cx))
+        .unwrap();
+    assert_eq!(history(&router, cx), entries(&["/", "/login"], 1));
+    router
+        .update(cx, |router, cx| router.replace("/admin", cx))
+        .unwrap();
+    assert_eq!(history(&router, cx), entries(&["/", "/login"], 1));
+    router
+        .update(cx, |router, cx| router.navigate_by_id(admin, &[], cx))
+        .unwrap();
+    assert_eq!(history(&router, cx), entries(&["/", "/login", "/login"], 2));
+
+    guard.set(GuardDecision::Allow);
+    router
+        .update(cx, |router, cx| router.replace("/admin", cx))
+        .unwrap();
+    router
+        .update(cx, |router, cx| router.navigate("/about", cx))
+        .unwrap();
+
+    guard.set(GuardDecision::Redirect("/login".to_string()));
+    assert_eq!(router.update(cx, |router, cx| router.back(cx)), Ok(true));
+    assert_eq!(route(&router, cx), "/login");
+    assert_eq!(
+        history(&router, cx),
+        entries(&["/", "/login", "/login", "/about"], 2)
+    );
+
+    guard.set(GuardDecision::Allow);
+    router
+        .update(cx, |router, cx| router.replace("/admin", cx))
+        .unwrap();
+    router.update(cx, |router, cx| router.back(cx)).unwrap();
+
+    guard.set(GuardDecision::Redirect("/login".to_string()));
+    assert_eq!(router.update(cx, |router, cx| router.forward(cx)), Ok(true));
+    assert_eq!(route(&router, cx), "/login");
+    assert_eq!(
+        history(&router, cx),
+        entries(&["/", "/login", "/login", "/about"], 2)
+    );
+}
+
+#[gpui::test]
+fn route_changes_are_emitted_to_subscribers(cx: &mut TestAppContext) {
+    let pages = [
+        ("index.rs", blank()),
+        ("about.rs", blank()),
+        ("locked.rs", blank()),
+        ("users/[id].rs", blank()),
+    ];
+    let mut router = RouterView::new(table(&pages), "/");
+    router.before_navigate_at("/locked", |_, _| GuardDecision::Cancel);
+    let (router, _) = open(cx, router);
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    cx.update(|cx| {
+        let events = events.clone();
+        cx.subscribe(&router, move |_, changed: &RouteChanged, _| {
+            let from = changed.from.as_ref().map(|from| from.spec.path.clone());
+            events
+                .borrow_mut()
+                .push((from, changed.to.spec.path.clone(), changed.kind));
+        })
+        .detach();
+    });
+    let event = |from: Option<&str>, to: &str, kind| {
+        (from.map(str::to_string), to.to_string(), kind)
+    };
+
+    router
+        .update(cx, |router, cx| router.navigate("/about", cx))
+        .unwrap();
+    router
+        .update(cx, |router, cx| router.replace("/users/7", cx))
+        .unwrap();
+    router.update(cx, |router, cx| router.back(cx)).unwrap();
+    router.update(cx, |router, cx| router.forward(cx)).unwrap();
+    assert_eq!(
+        events.borrow().clone(),
+        vec![
+            event(Some("/"), "/about", NavigationKind::Push),
+            event(Some("/about"), "/users/:id", NavigationKind::Replace),
+            event(Some("/users/:id"), "/", NavigationKind::Back),
+            event(Some("/"), "/users/:id", NavigationKind::Forward),
+        ]
+    );
+    events.borrow_mut().clear();
+
+    assert_eq!(
+        router.update(cx, |router, cx| router.navigate("/locked", cx)),
+        Err(NavigateError::Cancelled)
+    );
+    router
+        .update(cx, |router, cx| router.navigate("/missing", cx))
+        .unwrap();
+    assert!(events.borrow().is_empty());
+
+    router
+        .update(cx, |router, cx| router.navigate("/about", cx))
+        .unwrap();
+    assert_eq!(
+        events.borrow().clone(),
+        vec![event(None, "/about", NavigationKind::Push)]
+    );
+}
+
+#[gpui::test]
+fn history_actions_keys_and_mouse_buttons_move_through_history(cx: &mut TestAppContext) {
+    let pages = [("index.rs", blank()), ("about.rs", blank()), ("users/[id].rs", blank())];
+    let (router, window) = open(cx, RouterView::new(table(&pages), "/"));
+    for path in ["/about", "/users/1"] {
+        router
+            .update(cx, |router, cx| router.navigate(path, cx))
+            .unwrap();
+    }
+    cx.run_until_parked();
+
+    cx.dispatch_action(window, Back);
+    assert_eq!(route(&router, cx), "/about");
+    cx.dispatch_action(window, Forward);
+    assert_eq!(route(&router, cx), "/users/1");
+
+    cx.update(bind_history_keys);
+    cx.simulate_keystrokes(window, "alt-left");
+    assert_eq!(route(&router, cx), "/about");
+    cx.simulate_keystrokes(window, "alt-right");
+    assert_eq!(route(&router, cx), "/users/1");
+
+    let cx = &mut VisualTestContext::from_window(window, cx);
+    let position = point(px(10.0), px(10.0));
+    cx.simulate_mouse_down(
+        position,
+        MouseButton::Navigate(NavigationDirection::Back),
+        Modifiers::none(),
+    );
+    assert_eq!(cx.read(|cx| router.read(cx).route().to_string()), "/about");
+    cx.simulate_mouse_down(
+        position,
+        MouseButton::Navigate(NavigationDirection::Forward),
+        Modifiers::none(),
+    );
+    assert_eq!(cx.read(|cx| router.read(cx).route().to_string()), "/users/1");
+}
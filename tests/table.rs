@@ -1,12 +1,21 @@
+mod common;
+
+use common::page;
+use fs_router::core::conflict::ConflictKind;
 use fs_router::{RouteError, RouteKind, RouteSpec, RouteTable};
 
 fn spec(path: &str, kind: RouteKind) -> RouteSpec {
+    spec_with_id(0, path, kind)
+}
+
+fn spec_with_id(id: u64, path: &str, kind: RouteKind) -> RouteSpec {
     RouteSpec {
-        id: 0,
+        id,
         path: path.to_string(),
         kind,
         params: vec![],
         source: "".to_string(),
+        layouts: Vec::new(),
     }
 }
 
@@ -27,11 +36,301 @@ fn insert_rejects_duplicate_paths() {
 fn insert_rejects_multiple_fallbacks() {
     let mut table = RouteTable::new();
 
-    table.insert(spec("//*", RouteKind::Fallback), 1).unwrap();
-    let err = table.insert(spec("//*", RouteKind::Fallback), 2).unwrap_err();
+    table.insert(spec("/*", RouteKind::Fallback), 1).unwrap();
+    let err = table.insert(spec("/*", RouteKind::Fallback), 2).unwrap_err();
 
     match err {
         RouteError::MultipleFallbacks => {}
         other => panic!("unexpected error: {other:?}"),
     }
 }
+
+#[test]
+fn insert_rejects_duplicate_ids() {
+    let mut table = RouteTable::new();
+
+    table.insert(spec_with_id(7, "/ab", RouteKind::Static), 1).unwrap();
+    let err = table.insert(spec_with_id(7, "/ba", RouteKind::Static), 2).unwrap_err();
+
+    match err {
+        RouteError::DuplicateId(id) => assert_eq!(id, 7),
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[test]
+fn from_routes_rejects_duplicate_ids() {
+    let err = RouteTable::from_routes([
+        (spec_with_id(7, "/ab", RouteKind::Static), 1),
+        (spec_with_id(7, "/ba", RouteKind::Static), 2),
+    ])
+    .unwrap_err();
+
+    match err {
+        RouteError::DuplicateId(id) => assert_eq!(id, 7),
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[test]
+fn ids_are_unique_across_routes_fallbacks_and_layouts() {
+    let mut table = RouteTable::new();
+    table.insert(spec_with_id(7, "/ab", RouteKind::Static), 1).unwrap();
+
+    for kind in [RouteKind::Fallback, RouteKind::Layout] {
+        match table.insert(spec_with_id(7, "/*", kind), 2).unwrap_err() {
+            RouteError::DuplicateId(id) => assert_eq!(id, 7),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    let err = RouteTable::from_routes([
+        (spec_with_id(7, "/*", RouteKind::Layout), 1),
+        (spec_with_id(7, "/*", RouteKind::Fallback), 2),
+    ])
+    .unwrap_err();
+    assert!(matches!(err, RouteError::DuplicateId(7)));
+
+    let err = RouteTable::from_routes([
+        (spec_with_id(7, "/*", RouteKind::Fallback), 1),
+        (spec_with_id(7, "/ab", RouteKind::Static), 2),
+    ])
+    .unwrap_err();
+    assert!(matches!(err, RouteError::DuplicateId(7)));
+
+    assert_eq!(table.remove(7).map(|(_, handler)| handler), Some(1));
+}
+
+#[test]
+fn insert_rejects_structurally_equal_routes() {
+    let mut table = RouteTable::new();
+
+    table.insert(page("pages/users/[id].rs"), 1).unwrap();
+    let err = table.insert(page("pages/users/[user_id].rs"), 2).unwrap_err();
+
+    match err {
+        RouteError::ShadowedRoute(conflict) => {
+            assert_eq!(conflict.kind, ConflictKind::Shadowed);
+            assert_eq!(conflict.source, "pages/users/[user_id].rs");
+            assert_eq!(conflict.existing_source, "pages/users/[id].rs");
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[test]
+fn from_routes_rejects_structurally_equal_routes() {
+    let err = RouteTable::from_routes([
+        (page("pages/users/[id].rs"), 1),
+        (page("pages/users/[user_id].rs"), 2),
+    ])
+    .unwrap_err();
+
+    assert!(matches!(err, RouteError::ShadowedRoute(_)));
+}
+
+#[test]
+fn conflicts_lists_ambiguous_routes() {
+    let table = RouteTable::from_routes([
+        (page("pages/[org]/settings.rs"), 1),
+        (page("pages/users/[id].rs"), 2),
+        (page("pages/users/[id]/posts.rs"), 3),
+    ])
+    .unwrap();
+
+    let conflicts = table.conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].kind, ConflictKind::Ambiguous);
+    assert_eq!(conflicts[0].source, "pages/users/[id].rs");
+    assert_eq!(conflicts[0].existing_source, "pages/[org]/settings.rs");
+}
+
+#[test]
+fn conflicts_ignores_routes_with_different_scores() {
+    let table = RouteTable::from_routes([
+        (page("pages/users/new.rs"), 1),
+        (page("pages/users/[id].rs"), 2),
+    ])
+    .unwrap();
+
+    assert!(table.conflicts().is_empty());
+}
+
+#[test]
+fn nested_index_duplicates_its_sibling_file() {
+    let blog = page("pages/blog.rs");
+    let blog_index = page("pages/blog/index.rs");
+
+    let err = RouteTable::from_routes([(blog, 1), (blog_index, 2)]).unwrap_err();
+
+    match err {
+        RouteError::DuplicateRoute(path) => assert_eq!(path, "/blog"),
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[test]
+fn nearest_fallback_wins() {
+    let mut table = RouteTable::new();
+
+    table.insert(page("pages/404.rs"), "root").unwrap();
+    table.insert(page("pages/docs/404.rs"), "docs").unwrap();
+    table.insert(page("pages/docs/index.rs"), "index").unwrap();
+
+    assert_eq!(table.fallbacks().len(), 2);
+    assert_eq!(*table.fallback_for("/missing").unwrap().1, "root");
+    assert_eq!(*table.fallback_for("/docs/a/b").unwrap().1, "docs");
+    assert_eq!(*table.resolve_or_fallback("/docs").unwrap().1, "index");
+    assert_eq!(*table.resolve_or_fallback("/docs/missing").unwrap().1, "docs");
+}
+
+#[test]
+fn layouts_apply_to_pages_in_their_directory() {
+    let table = RouteTable::from_routes(
+        [
+            "pages/_layout.rs",
+            "pages/settings/_layout.rs",
+            "pages/settings/index.rs",
+            "pages/settings/profile.rs",
+            "pages/about.rs",
+        ]
+        .map(|source| (page(source), source)),
+    )
+    .unwrap();
+
+    assert_eq!(table.layouts().len(), 2);
+    assert_eq!(table.routes().len(), 3);
+    assert!(table.resolve("/settings/other").is_none());
+
+    let layouts_of = |path: &str| {
+        let matched = table.resolve(path).unwrap();
+        table
+            .layouts_for(&matched.spec)
+            .into_iter()
+            .map(|(_, source)| *source)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        layouts_of("/settings/profile"),
+        ["pages/_layout.rs", "pages/settings/_layout.rs"]
+    );
+    assert_eq!(
+        layouts_of("/settings"),
+        ["pages/_layout.rs", "pages/settings/_layout.rs"]
+    );
+    assert_eq!(layouts_of("/about"), ["pages/_layout.rs"]);
+}
+
+#[test]
+fn groups_producing_the_same_path_are_duplicates() {
+    let marketing = page("pages/(marketing)/about.rs");
+    let company = page("pages/(company)/about.rs");
+    assert_ne!(marketing.id, company.id);
+
+    let err = RouteTable::from_routes([(marketing, 1), (company, 2)]).unwrap_err();
+
+    match err {
+        RouteError::DuplicateRoute(path) => assert_eq!(path, "/about"),
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[test]
+fn optional_segments_take_part_in_conflicts() {
+    let parse = |source| (page(source), source);
+
+    let table = RouteTable::from_routes([
+        parse("pages/[[lang]]/docs.rs"),
+        parse("pages/docs/[[page]].rs"),
+    ])
+    .unwrap();
+
+    let conflicts = table.conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].kind, ConflictKind::Ambiguous);
+    assert_eq!(conflicts[0].source, "pages/docs/[[page]].rs");
+    assert_eq!(conflicts[0].existing_source, "pages/[[lang]]/docs.rs");
+
+    let table = RouteTable::from_routes([
+        parse("pages/pricing.rs"),
+        parse("pages/[[lang]]/pricing.rs"),
+    ])
+    .unwrap();
+    assert!(table.conflicts().is_empty());
+
+    let err = RouteTable::from_routes([
+        parse("pages/[[lang]]/pricing.rs"),
+        parse("pages/[[locale]]/pricing.rs"),
+    ])
+    .unwrap_err();
+    assert!(matches!(err, RouteError::ShadowedRoute(_)));
+}
+
+#[test]
+fn differently_constrained_params_are_distinct() {
+    let parse = |source| (page(source), source);
+
+    let table = RouteTable::from_routes([
+        parse("pages/users/[id=integer].rs"),
+        parse("pages/users/[id=uuid]/index.rs"),
+        parse("pages/users/[name].rs"),
+        parse("pages/posts/[id=integer]/[[tab]].rs"),
+        parse("pages/posts/[slug=slug]/[[tab]].rs"),
+    ])
+    .unwrap();
+    assert!(table.conflicts().is_empty());
+
+    let err = RouteTable::from_routes([
+        parse("pages/users/[id=integer].rs"),
+        parse("pages/users/[n=integer]/index.rs"),
+    ])
+    .unwrap_err();
+    assert!(matches!(err, RouteError::ShadowedRoute(_)));
+}
+
+#[test]
+fn mixed_segments_take_part_in_conflicts() {
+    let parse = |source| (page(source), source);
+
+    let table = RouteTable::from_routes([
+        parse("pages/posts/post-[id].rs"),
+        parse("pages/posts/page-[id].rs"),
+        parse("pages/posts/[title]-draft.rs"),
+    ])
+    .unwrap();
+
+    let conflicts = table.conflicts();
+    assert_eq!(conflicts.len(), 2);
+    assert!(conflicts.iter().all(|conflict| conflict.kind == ConflictKind::Ambiguous));
+    assert!(conflicts.iter().all(|conflict| conflict.source == "pages/posts/[title]-draft.rs"));
+    assert_eq!(conflicts[0].existing_source, "pages/posts/post-[id].rs");
+    assert_eq!(conflicts[1].existing_source, "pages/posts/page-[id].rs");
+
+    let err = RouteTable::from_routes([
+        parse("pages/posts/post-[id].rs"),
+        parse("pages/posts/post-[n]/index.rs"),
+    ])
+    .unwrap_err();
+    assert!(matches!(err, RouteError::ShadowedRoute(_)));
+}
+
+#[test]
+fn removing_a_route_recompiles_the_matcher() {
+    let mut table = RouteTable::from_routes([
+        (spec_with_id(1, "/a", RouteKind::Static), "a"),
+        (spec_with_id(2, "/b", RouteKind::Static), "b"),
+    ])
+    .unwrap();
+    assert_eq!(table.resolve_handler("/b").unwrap().1, &"b");
+
+    let (removed, handler) = table.remove(1).unwrap();
+    assert_eq!((removed.path.as_str(), handler), ("/a", "a"));
+    assert!(table.remove(1).is_none());
+
+    assert!(table.resolve("/a").is_none());
+    assert_eq!(table.resolve_handler("/b").unwrap().1, &"b");
+
+    *table.handler_mut(2).unwrap() = "b2";
+    assert_eq!(table.resolve_handler("/b").unwrap().1, &"b2");
+}